@@ -0,0 +1,76 @@
+// ==========================================================
+// BRDF metallic-roughness: GGX + Smith + Fresnel-Schlick (Cook-Torrance)
+// ==========================================================
+use std::f32::consts::PI;
+use crate::math::Vec3;
+use crate::lighting::{build_onb, Rng};
+
+/// Parámetros ya resueltos en el punto de impacto.
+pub struct Surface {
+   pub base: Vec3,   // color base (albedo × textura)
+   pub f0: Vec3,     // reflectancia a incidencia normal
+   pub metallic: f32,
+   pub alpha: f32,   // roughness²
+}
+
+impl Surface {
+   pub fn new(base: Vec3, metallic: f32, roughness: f32, specular: f32) -> Self {
+      let metallic = metallic.clamp(0.0, 1.0);
+      let d = 0.08 * specular.clamp(0.0, 1.0);
+      let f0 = Vec3::new(d, d, d).mul(1.0 - metallic).add(base.mul(metallic));
+      let r = roughness.clamp(0.03, 1.0);
+      Self { base, f0, metallic, alpha: r * r }
+   }
+}
+
+pub fn fresnel(cos_theta: f32, f0: Vec3) -> Vec3 {
+   let k = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+   f0.add(Vec3::new(1.0, 1.0, 1.0).sub(f0).mul(k))
+}
+
+// Distribución de normales GGX / Trowbridge-Reitz
+fn ggx_d(nh: f32, alpha: f32) -> f32 {
+   let a2 = alpha * alpha;
+   let d = nh * nh * (a2 - 1.0) + 1.0;
+   a2 / (PI * d * d)
+}
+
+// Sombreado/enmascarado de Smith (separable, Schlick-GGX)
+fn smith_g(nv: f32, nl: f32, alpha: f32) -> f32 {
+   let k = alpha * 0.5;
+   let g1 = |c: f32| c / (c * (1.0 - k) + k);
+   g1(nv) * g1(nl)
+}
+
+/// Radiancia reflejada hacia `v` por una luz de intensidad 1 en dirección `l`
+/// (difuso + especular, ya multiplicado por n·l). Escalado por π para que una
+/// superficie blanca difusa dé lo mismo que el Lambert del modelo Legacy.
+pub fn eval(s: &Surface, n: Vec3, v: Vec3, l: Vec3) -> Vec3 {
+   let nl = n.dot(l);
+   let nv = n.dot(v).max(1e-4);
+   if nl <= 0.0 { return Vec3::new(0.0, 0.0, 0.0); }
+   let h = v.add(l).normalize();
+   let f = fresnel(v.dot(h), s.f0);
+   let spec = f.mul(ggx_d(n.dot(h).max(0.0), s.alpha) * smith_g(nv, nl, s.alpha) / (4.0 * nv * nl));
+   let kd = Vec3::new(1.0, 1.0, 1.0).sub(f).mul(1.0 - s.metallic);
+   let diffuse = kd.hadamard(s.base).mul(1.0 / PI);
+   diffuse.add(spec).mul(nl * PI)
+}
+
+/// Muestrea una dirección de reflexión especular según GGX alrededor de `n`.
+/// Devuelve la dirección y el peso del estimador (F·G·(v·h) / ((n·h)(n·v))).
+pub fn sample_specular(s: &Surface, n: Vec3, v: Vec3, rng: &mut Rng) -> Option<(Vec3, Vec3)> {
+   let (r1, r2) = (rng.next_f32(), rng.next_f32());
+   let a2 = s.alpha * s.alpha;
+   let cos_t = ((1.0 - r2) / (1.0 + (a2 - 1.0) * r2)).max(0.0).sqrt();
+   let sin_t = (1.0 - cos_t * cos_t).max(0.0).sqrt();
+   let phi = 2.0 * PI * r1;
+   let (t, b, nn) = build_onb(n);
+   let h = t.mul(sin_t * phi.cos()).add(b.mul(sin_t * phi.sin())).add(nn.mul(cos_t)).normalize();
+
+   let l = Vec3::reflect(v.mul(-1.0), h).normalize();
+   let (nl, nv, nh, vh) = (n.dot(l), n.dot(v).max(1e-4), n.dot(h).max(1e-4), v.dot(h).max(0.0));
+   if nl <= 0.0 { return None; }
+   let weight = fresnel(vh, s.f0).mul(smith_g(nv, nl, s.alpha) * vh / (nh * nv));
+   Some((l, weight))
+}
@@ -0,0 +1,191 @@
+use crate::{math::Vec3, ray::Ray, material::Hit};
+
+// ==========================================================
+// AABB
+// ==========================================================
+#[derive(Copy, Clone, Debug)]
+pub struct Aabb { pub min: Vec3, pub max: Vec3 }
+
+impl Aabb {
+   pub fn new(min: Vec3, max: Vec3) -> Self { Self { min, max } }
+
+   /// Caja vacía (neutra para `union`)
+   pub fn empty() -> Self {
+      Self { min: Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY), max: Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY) }
+   }
+
+   pub fn union(self, o: Aabb) -> Aabb { Aabb { min: self.min.min(o.min), max: self.max.max(o.max) } }
+   pub fn grow(self, p: Vec3) -> Aabb { Aabb { min: self.min.min(p), max: self.max.max(p) } }
+   pub fn centroid(self) -> Vec3 { self.min.add(self.max).mul(0.5) }
+
+   pub fn surface_area(self) -> f32 {
+      let d = self.max.sub(self.min);
+      if d.x < 0.0 || d.y < 0.0 || d.z < 0.0 { return 0.0; }
+      2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+   }
+
+   /// Test por slabs con la inversa de la dirección ya calculada. Devuelve t de entrada.
+   pub fn hit(&self, ray: &Ray, inv: Vec3, tmin: f32, tmax: f32) -> Option<f32> {
+      let (tx1, tx2) = ((self.min.x - ray.origin.x) * inv.x, (self.max.x - ray.origin.x) * inv.x);
+      let (ty1, ty2) = ((self.min.y - ray.origin.y) * inv.y, (self.max.y - ray.origin.y) * inv.y);
+      let (tz1, tz2) = ((self.min.z - ray.origin.z) * inv.z, (self.max.z - ray.origin.z) * inv.z);
+
+      let t_enter = tmin.max(tx1.min(tx2)).max(ty1.min(ty2)).max(tz1.min(tz2));
+      let t_exit = tmax.min(tx1.max(tx2)).min(ty1.max(ty2)).min(tz1.max(tz2));
+      if t_enter <= t_exit { Some(t_enter) } else { None }
+   }
+}
+
+// ==========================================================
+// BVH (SAH por bins)
+// ==========================================================
+const SAH_BINS: usize = 12;
+const MAX_LEAF: usize = 4;
+const MAX_DEPTH: usize = 48;   // acota también la pila de recorrido
+const COST_TRAVERSAL: f32 = 1.0;
+const COST_INTERSECT: f32 = 1.0;
+
+// Hoja: count > 0, primitivas en prims[start..start+count].
+// Interno: hijo izquierdo = índice + 1 (orden en profundidad), derecho = `right`.
+#[derive(Copy, Clone, Debug)]
+struct Node { bounds: Aabb, start: u32, count: u32, right: u32 }
+
+pub struct Bvh {
+   nodes: Vec<Node>,
+   prims: Vec<usize>,
+}
+
+impl Bvh {
+   /// Construye el árbol a partir de (id de primitiva, caja). Los ids se devuelven tal cual en `trace`.
+   pub fn build(mut items: Vec<(usize, Aabb)>) -> Self {
+      let mut bvh = Bvh { nodes: Vec::with_capacity(items.len() * 2), prims: Vec::with_capacity(items.len()) };
+      if !items.is_empty() {
+         bvh.build_node(&mut items, 0, 0);
+         bvh.prims = items.iter().map(|(id, _)| *id).collect();
+      }
+      bvh
+   }
+
+   fn build_node(&mut self, items: &mut [(usize, Aabb)], offset: usize, depth: usize) -> usize {
+      let bounds = items.iter().fold(Aabb::empty(), |b, (_, bb)| b.union(*bb));
+      let idx = self.nodes.len();
+      self.nodes.push(Node { bounds, start: offset as u32, count: items.len() as u32, right: 0 });
+
+      if items.len() <= 1 || depth >= MAX_DEPTH { return idx; }
+
+      let Some((axis, split, cost)) = sah_split(items) else { return idx; };
+      let leaf_cost = COST_INTERSECT * items.len() as f32;
+      let split_cost = COST_TRAVERSAL + cost / bounds.surface_area().max(1e-12);
+      if split_cost >= leaf_cost && items.len() <= MAX_LEAF { return idx; }
+
+      // Partición in-place según el centroide
+      let mut mid = 0;
+      for j in 0..items.len() {
+         if items[j].1.centroid().axis(axis) < split { items.swap(mid, j); mid += 1; }
+      }
+      if mid == 0 || mid == items.len() { return idx; }
+
+      self.nodes[idx].count = 0;
+      let (left, right) = items.split_at_mut(mid);
+      self.build_node(left, offset, depth + 1);
+      let r = self.build_node(right, offset + mid, depth + 1);
+      self.nodes[idx].right = r as u32;
+      idx
+   }
+
+   /// Recorre el árbol; `hit_prim(id, t_closest)` intersecta una primitiva con el t más cercano actual.
+   pub fn trace<'a, F>(&self, ray: Ray, tmin: f32, tmax: f32, mut hit_prim: F) -> Option<Hit<'a>>
+   where F: FnMut(usize, f32) -> Option<Hit<'a>> {
+      if self.nodes.is_empty() { return None; }
+      let inv = Vec3::new(1.0 / ray.dir.x, 1.0 / ray.dir.y, 1.0 / ray.dir.z);
+      self.nodes[0].bounds.hit(&ray, inv, tmin, tmax)?;
+
+      let mut closest = tmax;
+      let mut best: Option<Hit> = None;
+      let mut stack = [(0u32, 0.0f32); MAX_DEPTH + 1];
+      let mut sp = 0;
+      let mut node = 0usize;
+
+      loop {
+         let n = self.nodes[node];
+         if n.count > 0 {
+            let (s, e) = (n.start as usize, (n.start + n.count) as usize);
+            for &id in &self.prims[s..e] {
+               if let Some(h) = hit_prim(id, closest) {
+                  closest = h.t;
+                  best = Some(h);
+               }
+            }
+         } else {
+            let (l, r) = (node + 1, n.right as usize);
+            let tl = self.nodes[l].bounds.hit(&ray, inv, tmin, closest);
+            let tr = self.nodes[r].bounds.hit(&ray, inv, tmin, closest);
+            match (tl, tr) {
+               (Some(a), Some(b)) => {
+                  // Primero el hijo más cercano; el otro a la pila
+                  let (near, far, t_far) = if a <= b { (l, r, b) } else { (r, l, a) };
+                  stack[sp] = (far as u32, t_far);
+                  sp += 1;
+                  node = near;
+                  continue;
+               }
+               (Some(_), None) => { node = l; continue; }
+               (None, Some(_)) => { node = r; continue; }
+               (None, None) => {}
+            }
+         }
+
+         // Saco de la pila descartando nodos que ya quedan detrás del hit más cercano
+         loop {
+            if sp == 0 { return best; }
+            sp -= 1;
+            let (next, t_enter) = stack[sp];
+            if t_enter <= closest { node = next as usize; break; }
+         }
+      }
+   }
+}
+
+/// Mejor corte SAH por bins sobre los centroides: (eje, posición, coste sin normalizar).
+fn sah_split(items: &[(usize, Aabb)]) -> Option<(usize, f32, f32)> {
+   let cb = items.iter().fold(Aabb::empty(), |b, (_, bb)| b.grow(bb.centroid()));
+   let mut best: Option<(usize, f32, f32)> = None;
+
+   for axis in 0..3 {
+      let lo = cb.min.axis(axis);
+      let hi = cb.max.axis(axis);
+      if hi - lo <= 1e-6 { continue; }
+      let scale = SAH_BINS as f32 / (hi - lo);
+
+      let mut bins = [(Aabb::empty(), 0usize); SAH_BINS];
+      for (_, bb) in items {
+         let b = (((bb.centroid().axis(axis) - lo) * scale) as usize).min(SAH_BINS - 1);
+         bins[b].0 = bins[b].0.union(*bb);
+         bins[b].1 += 1;
+      }
+
+      // Barrido derecha -> izquierda para áreas/cuentas acumuladas
+      let mut right_area = [0.0f32; SAH_BINS];
+      let mut right_count = [0usize; SAH_BINS];
+      let (mut acc, mut cnt) = (Aabb::empty(), 0);
+      for i in (1..SAH_BINS).rev() {
+         acc = acc.union(bins[i].0);
+         cnt += bins[i].1;
+         right_area[i] = acc.surface_area();
+         right_count[i] = cnt;
+      }
+
+      let (mut acc, mut cnt) = (Aabb::empty(), 0);
+      for i in 0..SAH_BINS - 1 {
+         acc = acc.union(bins[i].0);
+         cnt += bins[i].1;
+         let (rc, lc) = (right_count[i + 1], cnt);
+         if lc == 0 || rc == 0 { continue; }
+         let cost = COST_INTERSECT * (acc.surface_area() * lc as f32 + right_area[i + 1] * rc as f32);
+         if best.is_none_or(|(_, _, c)| cost < c) {
+            best = Some((axis, lo + (i + 1) as f32 / scale, cost));
+         }
+      }
+   }
+   best
+}
@@ -1,5 +1,6 @@
 use crate::math::Vec3;
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct OrbitCam {
    pub target: Vec3,
    pub yaw: f32,
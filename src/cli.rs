@@ -0,0 +1,121 @@
+// Argumentos de línea de comandos (sin dependencias externas)
+use crate::tonemap::{Operator, Tonemap};
+
+pub const USAGE: &str = "\
+uso:
+  computer-graphics-v3 [opts]          abre el visor interactivo (raylib)
+  computer-graphics-v3 render [opts]   render offline sin ventana
+
+opciones del visor:
+  --width N         ancho de render en píxeles (default 320)
+  --height N        alto de render en píxeles (default 180)
+  --scale N         factor de ampliación de la ventana, 1..=8 (default 4)
+  --scene ARCHIVO   carga la escena desde un archivo .scene (default: isla integrada)
+  --tonemap OP      clamp | reinhard | aces | hable | agx (default aces; T los recorre)
+  --exposure EV     exposición en pasos (default 0; , y . la bajan/suben)
+
+opciones de render:
+  --width N         ancho en píxeles (default 1280)
+  --height N        alto en píxeles (default 720)
+  --samples N       frames acumulados por píxel (default 64)
+  --env-samples N   muestras de IBL por hit y frame, 0 = sin IBL (default 4)
+  --angle GRADOS    rotación del diorama (default 0)
+  --out ARCHIVO     salida .png o .ppm (default render.png)
+  --scene ARCHIVO   carga la escena desde un archivo .scene (default: isla integrada)
+  --tonemap OP      clamp | reinhard | aces | hable | agx (default aces)
+  --exposure EV     exposición en pasos (default 0)";
+
+pub const MAX_SCALE: i32 = 8;
+
+pub struct ViewerOpts {
+   pub width: i32,
+   pub height: i32,
+   pub scale: i32,
+   pub scene: Option<String>,
+   pub tonemap: Tonemap,
+}
+
+impl Default for ViewerOpts {
+   fn default() -> Self { Self { width: 320, height: 180, scale: 4, scene: None, tonemap: Tonemap::default() } }
+}
+
+pub struct RenderOpts {
+   pub width: i32,
+   pub height: i32,
+   pub samples: u32,
+   pub env_samples: u32,
+   pub world_angle: f32,   // radianes
+   pub out: String,
+   pub scene: Option<String>,
+   pub tonemap: Tonemap,
+}
+
+impl Default for RenderOpts {
+   fn default() -> Self {
+      Self { width: 1280, height: 720, samples: 64, env_samples: 4, world_angle: 0.0, out: "render.png".to_string(), scene: None, tonemap: Tonemap::default() }
+   }
+}
+
+pub enum Command {
+   Viewer(ViewerOpts),
+   Render(RenderOpts),
+   Help,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Command, String> {
+   let mut args = args.peekable();
+   match args.peek().map(String::as_str) {
+      Some("render") => { args.next(); parse_render(args).map(Command::Render) }
+      Some("-h" | "--help" | "help") => Ok(Command::Help),
+      _ => parse_viewer(args).map(Command::Viewer),
+   }
+}
+
+fn parse_viewer(mut args: impl Iterator<Item = String>) -> Result<ViewerOpts, String> {
+   let mut o = ViewerOpts::default();
+   while let Some(flag) = args.next() {
+      let mut value = || args.next().ok_or_else(|| format!("falta el valor de {flag}"));
+      match flag.as_str() {
+         "--width" => o.width = number(&flag, &value()?)?,
+         "--height" => o.height = number(&flag, &value()?)?,
+         "--scale" => o.scale = number(&flag, &value()?)?,
+         "--scene" => o.scene = Some(value()?),
+         "--tonemap" => o.tonemap.op = operator(&value()?)?,
+         "--exposure" => o.tonemap.exposure = number(&flag, &value()?)?,
+         _ => return Err(format!("opción desconocida: {flag}")),
+      }
+   }
+   if o.width <= 0 || o.height <= 0 { return Err("la resolución debe ser positiva".to_string()); }
+   if !(1..=MAX_SCALE).contains(&o.scale) { return Err(format!("--scale debe estar entre 1 y {MAX_SCALE}")); }
+   Ok(o)
+}
+
+fn parse_render(mut args: impl Iterator<Item = String>) -> Result<RenderOpts, String> {
+   let mut o = RenderOpts::default();
+   while let Some(flag) = args.next() {
+      let mut value = || args.next().ok_or_else(|| format!("falta el valor de {flag}"));
+      match flag.as_str() {
+         "--width" => o.width = number(&flag, &value()?)?,
+         "--height" => o.height = number(&flag, &value()?)?,
+         "--samples" => o.samples = number(&flag, &value()?)?,
+         "--env-samples" => o.env_samples = number(&flag, &value()?)?,
+         "--angle" => o.world_angle = number::<f32>(&flag, &value()?)?.to_radians(),
+         "--out" => o.out = value()?,
+         "--scene" => o.scene = Some(value()?),
+         "--tonemap" => o.tonemap.op = operator(&value()?)?,
+         "--exposure" => o.tonemap.exposure = number(&flag, &value()?)?,
+         _ => return Err(format!("opción desconocida: {flag}")),
+      }
+   }
+   if o.width <= 0 || o.height <= 0 { return Err("la resolución debe ser positiva".to_string()); }
+   if o.samples == 0 { return Err("--samples debe ser al menos 1".to_string()); }
+   Ok(o)
+}
+
+fn operator(v: &str) -> Result<Operator, String> {
+   Operator::from_name(v).ok_or_else(|| format!("tonemap desconocido: {v} (clamp, reinhard, aces, hable, agx)"))
+}
+
+fn number<T: std::str::FromStr>(flag: &str, v: &str) -> Result<T, String> {
+   v.parse().map_err(|_| format!("valor inválido para {flag}: {v}"))
+}
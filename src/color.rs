@@ -0,0 +1,30 @@
+// ==========================================================
+// Espacios de color: sRGB (archivos / pantalla) ↔ lineal (todo el shading)
+// ==========================================================
+// Las imágenes de 8 bits vienen codificadas en sRGB: se decodifican a lineal al cargar.
+// El render trabaja en float lineal y solo al pasar a `Color` se aplica la OETF sRGB.
+use std::sync::OnceLock;
+use crate::math::Vec3;
+
+/// EOTF sRGB: valor codificado [0,1] → lineal.
+pub fn srgb_to_linear(c: f32) -> f32 {
+   if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// OETF sRGB: lineal [0,1] → valor codificado.
+pub fn linear_to_srgb(c: f32) -> f32 {
+   let c = c.clamp(0.0, 1.0);
+   if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Decodificación de un canal de 8 bits (tabla de 256 entradas, se arma una sola vez).
+pub fn decode8(c: u8) -> f32 {
+   static LUT: OnceLock<[f32; 256]> = OnceLock::new();
+   LUT.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0)))[c as usize]
+}
+
+/// Color lineal → bytes sRGB para pantalla / archivo.
+pub fn encode8(c: Vec3) -> [u8; 3] {
+   let q = |v: f32| (linear_to_srgb(v) * 255.0 + 0.5) as u8;
+   [q(c.x), q(c.y), q(c.z)]
+}
@@ -0,0 +1,98 @@
+use std::io::Write;
+use crate::{math::Vec3, color::encode8, tonemap::Tonemap};
+use crate::camera::OrbitCam;
+use raylib::prelude::{Color, Image};
+
+/// Framebuffer en float (RGB lineal HDR por píxel, sin recortar, fila a fila).
+pub struct FrameBuffer {
+   pub width: i32,
+   pub height: i32,
+   pub pixels: Vec<Vec3>,
+}
+
+impl FrameBuffer {
+   pub fn new(width: i32, height: i32) -> Self {
+      Self { width, height, pixels: vec![Vec3::default(); (width * height) as usize] }
+   }
+
+   /// Bytes RGBA8 listos para `Texture2D::update_texture`: tonemapping del HDR y OETF sRGB.
+   pub fn to_rgba8(&self, tm: &Tonemap) -> Vec<u8> {
+      let mut bytes = Vec::<u8>::with_capacity(self.pixels.len() * 4);
+      for c in &self.pixels {
+         bytes.extend_from_slice(&encode8(tm.apply(*c)));
+         bytes.push(255);
+      }
+      bytes
+   }
+
+   /// Guarda a disco: `.ppm` (P6) lo escribe este módulo; el resto lo exporta raylib, que elige
+   /// el formato por la extensión (`.png`, `.bmp`, `.tga`...) y falla sin extensión o con una
+   /// desconocida. Se escribe a un temporal y se renombra, así un error no pisa el archivo previo.
+   pub fn save(&self, path: &str, tm: &Tonemap) -> Result<(), String> {
+      let rgba = self.to_rgba8(tm);
+      // El temporal va junto al destino con la misma extensión (la que elige el formato)
+      let target = std::path::Path::new(path);
+      let name = target.file_name().and_then(|n| n.to_str()).ok_or_else(|| format!("{path}: ruta inválida"))?;
+      let tmp = target.with_file_name(format!(".tmp-{}-{name}", std::process::id()));
+      let tmp_str = tmp.to_str().ok_or_else(|| format!("{path}: ruta inválida"))?;
+      let _ = std::fs::remove_file(&tmp);
+
+      if path.to_lowercase().ends_with(".ppm") {
+         let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+         for px in rgba.chunks(4) { data.extend_from_slice(&px[..3]); }
+         let written = std::fs::File::create(&tmp).and_then(|mut f| f.write_all(&data));
+         if let Err(e) = written { let _ = std::fs::remove_file(&tmp); return Err(format!("{path}: {e}")); }
+      } else {
+         let mut img = Image::gen_image_color(self.width, self.height, Color::BLACK);
+         for (i, px) in rgba.chunks(4).enumerate() {
+            let (x, y) = (i as i32 % self.width, i as i32 / self.width);
+            img.draw_pixel(x, y, Color::new(px[0], px[1], px[2], px[3]));
+         }
+         // raylib no informa si la exportación falló: solo cuenta si el temporal apareció
+         img.export_image(tmp_str);
+         if !tmp.exists() { return Err(format!("{path}: no se pudo exportar la imagen")); }
+      }
+      std::fs::rename(&tmp, target).map_err(|e| { let _ = std::fs::remove_file(&tmp); format!("{path}: {e}") })
+   }
+}
+
+/// Estado de vista que invalida la acumulación cuando cambia.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct View {
+   pub cam: OrbitCam,
+   pub world_angle: f32,
+   pub scene_revision: u64,
+   pub env_samples: u32,
+}
+
+/// Promedio progresivo de frames mientras la vista se mantiene quieta.
+pub struct Accumulator {
+   sum: Vec<Vec3>,
+   frames: u32,
+   view: Option<View>,
+}
+
+impl Accumulator {
+   pub fn new() -> Self { Self { sum: Vec::new(), frames: 0, view: None } }
+
+   pub fn frames(&self) -> u32 { self.frames }
+
+   /// Suma `fb` al acumulado (reinicia si cambió la vista o el tamaño) y deja en `fb` el promedio.
+   pub fn accumulate(&mut self, fb: &mut FrameBuffer, view: View) {
+      if self.view != Some(view) || self.sum.len() != fb.pixels.len() {
+         self.sum = vec![Vec3::default(); fb.pixels.len()];
+         self.frames = 0;
+         self.view = Some(view);
+      }
+      self.frames += 1;
+      let inv = 1.0 / self.frames as f32;
+      for (acc, px) in self.sum.iter_mut().zip(fb.pixels.iter_mut()) {
+         *acc = acc.add(*px);
+         *px = acc.mul(inv);
+      }
+   }
+}
+
+impl Default for Accumulator {
+   fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,143 @@
+use std::f32::consts::PI;
+use crate::math::Vec3;
+use crate::lighting::Rng;
+
+/// Fuentes de luz puntuales/direccionales; todas se evalúan con rayos de sombra en `local_shade`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Light {
+   // Luz en el infinito (sol): `dir` es hacia dónde viaja la luz
+   Directional { dir: Vec3, color: Vec3, intensity: f32 },
+   // Caída con el inverso del cuadrado de la distancia
+   Point { pos: Vec3, color: Vec3, intensity: f32 },
+   // Puntual limitada a un cono: plena dentro de `inner`, se apaga hasta `outer` (radianes, semiángulos)
+   Spot { pos: Vec3, dir: Vec3, color: Vec3, intensity: f32, inner: f32, outer: f32 },
+}
+
+/// Luz que llega a un punto desde una fuente.
+pub struct Incident {
+   pub l: Vec3,        // dirección hacia la luz (normalizada)
+   pub dist: f32,      // distancia máxima del rayo de sombra
+   pub radiance: Vec3, // color × intensidad × atenuación
+}
+
+impl Light {
+   /// Sol blanco de intensidad 1.
+   pub fn sun(dir: Vec3) -> Self {
+      Light::Directional { dir, color: Vec3::new(1.0, 1.0, 1.0), intensity: 1.0 }
+   }
+
+   /// Las direccionales giran con el mundo (como el sol); puntuales y focos quedan fijos.
+   pub fn rotated(&self, world_angle: f32) -> Self {
+      match *self {
+         Light::Directional { dir, color, intensity } => Light::Directional { dir: dir.rot_y(world_angle), color, intensity },
+         other => other,
+      }
+   }
+
+   pub fn incident(&self, p: Vec3) -> Option<Incident> {
+      match *self {
+         Light::Directional { dir, color, intensity } => {
+            Some(Incident { l: dir.normalize().mul(-1.0), dist: 1e9, radiance: color.mul(intensity) })
+         }
+         Light::Point { pos, color, intensity } => {
+            let (l, d) = to_light(p, pos)?;
+            Some(Incident { l, dist: d, radiance: color.mul(intensity / (d * d)) })
+         }
+         Light::Spot { pos, dir, color, intensity, inner, outer } => {
+            let (l, d) = to_light(p, pos)?;
+            let cos_a = l.mul(-1.0).dot(dir.normalize());
+            let (cos_in, cos_out) = (inner.cos(), outer.max(inner).cos());
+            let cone = if cos_in > cos_out { ((cos_a - cos_out) / (cos_in - cos_out)).clamp(0.0, 1.0) } else if cos_a >= cos_in { 1.0 } else { 0.0 };
+            if cone <= 0.0 { return None; }
+            let cone = cone * cone * (3.0 - 2.0 * cone); // smoothstep
+            Some(Incident { l, dist: d, radiance: color.mul(intensity * cone / (d * d)) })
+         }
+      }
+   }
+}
+
+fn to_light(p: Vec3, pos: Vec3) -> Option<(Vec3, f32)> {
+   let v = pos.sub(p);
+   let d = v.length();
+   if d < 1e-4 { return None; }
+   Some((v.mul(1.0 / d), d))
+}
+
+/// Forma de una superficie emisora muestreable.
+#[derive(Copy, Clone, Debug)]
+pub enum AreaShape {
+   Box { min: Vec3, max: Vec3 },
+   Sphere { center: Vec3, radius: f32 },
+   // Triángulo de una malla emisiva (emite por las dos caras, como se ve en el trazado)
+   Triangle { a: Vec3, b: Vec3, c: Vec3 },
+}
+
+/// Luz de área: un objeto emisivo (bloque de glowstone, lava, esfera...) visto como fuente.
+#[derive(Copy, Clone, Debug)]
+pub struct AreaLight {
+   pub shape: AreaShape,
+   pub radiance: Vec3,
+}
+
+impl AreaLight {
+   /// Muestrea un punto de la superficie visible desde `p` (sombras suaves al acumular).
+   /// La radiancia devuelta ya incluye el ángulo sólido: Le·cosθ'·A / (π·d²), con la
+   /// misma escala que las luces puntuales (una superficie blanca bajo Le=1 que la
+   /// rodea por completo devuelve 1).
+   pub fn sample(&self, p: Vec3, rng: &mut Rng) -> Option<Incident> {
+      let (q, n_l, area) = match self.shape {
+         AreaShape::Box { min, max } => {
+            // Caras que miran hacia p (hasta 3), elegidas con probabilidad ∝ área
+            let ext = max.sub(min);
+            let mut faces = [(0usize, 0.0f32, 0.0f32); 3];
+            let mut count = 0;
+            let mut total = 0.0;
+            for a in 0..3 {
+               let side = if p.axis(a) > max.axis(a) { 1.0 } else if p.axis(a) < min.axis(a) { -1.0 } else { continue };
+               let face_area = ext.axis((a + 1) % 3) * ext.axis((a + 2) % 3);
+               faces[count] = (a, side, face_area);
+               count += 1;
+               total += face_area;
+            }
+            if count == 0 || total <= 0.0 { return None; }
+            let mut pick = rng.next_f32() * total;
+            let mut face = faces[count - 1];
+            for f in &faces[..count] {
+               if pick < f.2 { face = *f; break; }
+               pick -= f.2;
+            }
+            let (a, side, _) = face;
+            let (r1, r2) = (rng.next_f32(), rng.next_f32());
+            let mut c = [0.0f32; 3];
+            c[a] = if side > 0.0 { max.axis(a) } else { min.axis(a) };
+            c[(a + 1) % 3] = min.axis((a + 1) % 3) + r1 * ext.axis((a + 1) % 3);
+            c[(a + 2) % 3] = min.axis((a + 2) % 3) + r2 * ext.axis((a + 2) % 3);
+            let mut n = [0.0f32; 3];
+            n[a] = side;
+            (Vec3::new(c[0], c[1], c[2]), Vec3::new(n[0], n[1], n[2]), total)
+         }
+         AreaShape::Sphere { center, radius } => {
+            let (r1, r2) = (rng.next_f32(), rng.next_f32());
+            let z = 1.0 - 2.0 * r1;
+            let r = (1.0 - z * z).max(0.0).sqrt();
+            let phi = 2.0 * PI * r2;
+            let n = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+            (center.add(n.mul(radius)), n, 4.0 * PI * radius * radius)
+         }
+         AreaShape::Triangle { a, b, c } => {
+            // Punto uniforme en el triángulo; la normal se da vuelta hacia p
+            let (r1, r2) = (rng.next_f32().sqrt(), rng.next_f32());
+            let q = a.mul(1.0 - r1).add(b.mul(r1 * (1.0 - r2))).add(c.mul(r1 * r2));
+            let cross = b.sub(a).cross(c.sub(a));
+            let area = 0.5 * cross.length();
+            if area <= 0.0 { return None; }
+            let n = cross.normalize();
+            (q, if n.dot(p.sub(q)) < 0.0 { n.mul(-1.0) } else { n }, area)
+         }
+      };
+      let (l, d) = to_light(p, q)?;
+      let cos_l = n_l.dot(l.mul(-1.0));
+      if cos_l <= 0.0 { return None; }
+      Some(Incident { l, dist: d, radiance: self.radiance.mul(cos_l * area / (PI * d * d)) })
+   }
+}
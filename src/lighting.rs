@@ -31,7 +31,7 @@ fn cosine_hemisphere_sample(r1: f32, r2: f32) -> (f32, f32, f32) {
    (x, y, z) // en espacio tangente: y = normal
 }
 
-fn build_onb(n: Vec3) -> (Vec3, Vec3, Vec3) {
+pub fn build_onb(n: Vec3) -> (Vec3, Vec3, Vec3) {
    let n = n.normalize();
    let a = if n.x.abs() > 0.1 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
    let t = n.cross(a).normalize();
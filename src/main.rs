@@ -9,85 +9,154 @@ mod shapes;
 mod texture;
 mod skybox;
 mod lighting;
+mod light;
+mod brdf;
+mod spectrum;
+mod color;
+mod tonemap;
+mod noise;
+mod bvh;
+mod framebuffer;
+mod cli;
+mod scene_file;
+mod obj;
 
 // --- Imports ---
 use raylib::prelude::*;
 use math::Vec3;
 use material::Material;
-use shapes::Cube;
+use shapes::VoxelGrid;
+use shapes::voxel::BlockId;
+use shapes::cube::{Block, Orientation};
 use camera::OrbitCam;
-use render::{render_scene, W, H, SCALE};
-use texture::Texture;
+use light::Light;
+use render::render_scene;
+use texture::{Texture, Filter};
+use noise::{Noise, Pattern, Space};
+use std::sync::Arc;
 use skybox::Skybox;
+use color::srgb_to_linear;
+use framebuffer::{FrameBuffer, Accumulator, View};
+use cli::{Command, RenderOpts, ViewerOpts};
 
 // ==========================================================
 // Helpers personales
 // ==========================================================
-const BLOCK: f32 = 1.0;
-
-fn add_block(scene: &mut scene::Scene, gx: i32, gy: i32, gz: i32, mat: Material) {
-    let min = Vec3::new(gx as f32 * BLOCK, gy as f32 * BLOCK, gz as f32 * BLOCK);
-    let max = Vec3::new((gx + 1) as f32 as f32 * BLOCK, (gy + 1) as f32 as f32 * BLOCK, (gz + 1) as f32 as f32 * BLOCK);
-    scene.add(Box::new(Cube { min, max, mat }));
+// Bloques unitarios sobre la grilla de vóxeles
+fn add_block(world: &mut VoxelGrid, gx: i32, gy: i32, gz: i32, id: BlockId) {
+    world.set(gx, gy, gz, id);
 }
 
-// Materiales base (fallback a damero si falta imagen)
+// Los colores de la isla se eligieron a ojo en sRGB: pasan a lineal antes de usarse
+fn srgb(c: Vec3) -> Vec3 { Vec3::new(srgb_to_linear(c.x), srgb_to_linear(c.y), srgb_to_linear(c.z)) }
+
+// Materiales base (fallback a damero si falta imagen); albedo y damero en sRGB
 fn material_from(tex: Option<Texture>, albedo: Vec3, kd: f32, spec: f32, transp: f32, refl: f32, ior: f32, fallback_a: Vec3, fallback_b: Vec3, scale: f32) -> Material {
     Material {
-        albedo, kd, specular: spec, transparency: transp, reflectivity: refl, ior,
-        texture: tex.unwrap_or(Texture::Checker { scale, a: fallback_a, b: fallback_b }),
+        albedo: srgb(albedo), kd, specular: spec, transparency: transp, reflectivity: refl, ior,
+        texture: tex.unwrap_or(Texture::Checker { scale, a: srgb(fallback_a), b: srgb(fallback_b) }),
+        ..Material::default()
     }
 }
 
+// Texturas de la isla: trilineal para que el pasto lejano no parpadee al rotar
+fn load_tex(path: &str) -> Option<Texture> {
+    Texture::from_file(path).map(|t| t.with_filter(Filter::Trilinear))
+}
+
 // ==========================================================
-// Main
+// Escena: isla flotante con cueva
 // ==========================================================
-fn main() {
-    // Ventana
-    let (mut rl, th) = raylib::init()
-        .size(W * SCALE, H * SCALE)
-        .title("Rust Raytracer — Isla flotante con cueva")
-        .build();
-    rl.set_target_fps(60);
-
-    // Framebuffer
-    let mut image = Image::gen_image_color(W, H, Color::BLACK);
-    let mut tex = rl.load_texture_from_image(&th, &image).unwrap();
+// Prefiere un cielo HDR si existe; si no, el JPG de 8 bits
+fn load_sky() -> Option<Skybox> {
+    let path = if std::path::Path::new("assets/sky.hdr").exists() { "assets/sky.hdr" } else { "assets/sky.jpg" };
+    match Skybox::load(path) {
+        Ok(sky) => Some(sky),
+        Err(e) => { eprintln!("(info) {e} — usando gradiente."); None }
+    }
+}
 
-    // Skybox
-    let sky = Skybox::load("assets/sky.jpg");
-    if sky.is_none() { eprintln!("(info) No se encontró assets/sky.jpg — usando gradiente."); }
+// Escena desde archivo (--scene) o la isla integrada
+fn load_world(path: Option<&str>) -> (scene::Scene, OrbitCam, Option<Skybox>) {
+    let Some(path) = path else {
+        let sky = load_sky();
+        let (scene, cam) = build_island();
+        return (scene, cam, sky);
+    };
+    match scene_file::load(path) {
+        Ok(desc) => (desc.scene, desc.cam, desc.sky),
+        Err(e) => { eprintln!("error: {e}"); std::process::exit(1); }
+    }
+}
 
+fn build_island() -> (scene::Scene, OrbitCam) {
     // Cargo texturas
-    let tex_grass_top  = Texture::from_file("assets/frontgrass.png");
-    let tex_grass_side = Texture::from_file("assets/grass.png");
-    let tex_dirt       = Texture::from_file("assets/dirt.png");
-    let tex_stone      = Texture::from_file("assets/stone.png");
-    let tex_water      = Texture::from_file("assets/water.png");
-    let tex_wood       = Texture::from_file("assets/wood.png");
-    let tex_leaf       = Texture::from_file("assets/leaf.png");
-    let tex_diamond    = Texture::from_file("assets/diamond.png");
-    let tex_tnt        = Texture::from_file("assets/tnt.png");
+    let tex_grass_top  = load_tex("assets/frontgrass.png");
+    let tex_grass_side = load_tex("assets/grass.png");
+    let tex_dirt       = load_tex("assets/dirt.png");
+    let tex_stone      = load_tex("assets/stone.png");
+    let tex_water      = load_tex("assets/water.png");
+    let tex_wood       = load_tex("assets/wood.png");
+    let tex_leaf       = load_tex("assets/leaf.png");
+    let tex_diamond    = load_tex("assets/diamond.png");
+    let tex_tnt        = load_tex("assets/tnt.png");
 
     // Atlas de pasto (top/side/bottom)
     let grass_atlas = Texture::block_atlas_from_files(
         "assets/frontgrass.png",
         "assets/grass.png",
         "assets/dirt.png",
-    ).unwrap_or(Texture::Checker { scale: 8.0, a: Vec3::new(0.30,0.60,0.30), b: Vec3::new(0.20,0.45,0.20) });
+    ).map(|t| t.with_filter(Filter::Trilinear)).unwrap_or(Texture::Checker { scale: 8.0, a: srgb(Vec3::new(0.30,0.60,0.30)), b: srgb(Vec3::new(0.20,0.45,0.20)) });
 
     // Materiales
     let mat_grass = material_from(Some(grass_atlas), Vec3::new(1.0,1.0,1.0), 1.1, 0.05, 0.0, 0.0, 1.0, Vec3::new(0.3,0.6,0.3), Vec3::new(0.2,0.45,0.2), 8.0);
-    let mat_dirt  = material_from(tex_dirt,  Vec3::new(1.0,1.0,1.0), 1.0, 0.03, 0.0, 0.0, 1.0, Vec3::new(0.45,0.25,0.15), Vec3::new(0.30,0.18,0.10), 6.0);
-    let mat_stone = material_from(tex_stone, Vec3::new(0.95,0.95,0.95), 1.1, 0.02, 0.0, 0.0, 1.0, Vec3::new(0.65,0.65,0.70), Vec3::new(0.40,0.40,0.45), 10.0);
-    let mat_water = material_from(tex_water, Vec3::new(0.85,0.95,1.0), 0.1, 0.2, 0.9, 0.05, 1.33, Vec3::new(0.92,0.98,1.0), Vec3::new(0.84,0.94,1.0), 12.0);
-    let mat_wood  = material_from(tex_wood,  Vec3::new(1.0,1.0,1.0), 1.0, 0.10, 0.0, 0.0, 1.0, Vec3::new(0.60,0.40,0.20), Vec3::new(0.40,0.25,0.15), 6.0);
-    let mat_leaf  = material_from(tex_leaf,  Vec3::new(0.9,1.0,0.9), 1.0, 0.05, 0.0, 0.0, 1.0, Vec3::new(0.20,0.45,0.20), Vec3::new(0.15,0.35,0.15), 10.0);
-    let mat_diamond = material_from(tex_diamond, Vec3::new(1.0,1.0,1.0), 0.9, 0.4, 0.0, 0.2, 1.0, Vec3::new(0.6,0.9,1.0), Vec3::new(0.4,0.7,0.9), 8.0);
+    // Relieve derivado de la propia textura (sin assets extra); sin imagen queda plano
+    let bump = |t: &Option<Texture>, s: f32| t.as_ref().and_then(|t| t.normal_map_from_height(s)).unwrap_or(Texture::None);
+    let (nrm_dirt, nrm_stone) = (bump(&tex_dirt, 3.0), bump(&tex_stone, 4.0));
+    let mat_dirt  = Material { normal_map: nrm_dirt, ..material_from(tex_dirt,  Vec3::new(1.0,1.0,1.0), 1.0, 0.03, 0.0, 0.0, 1.0, Vec3::new(0.45,0.25,0.15), Vec3::new(0.30,0.18,0.10), 6.0) };
+    let mat_stone = Material { normal_map: nrm_stone, ..material_from(tex_stone, Vec3::new(0.95,0.95,0.95), 1.1, 0.02, 0.0, 0.0, 1.0, Vec3::new(0.65,0.65,0.70), Vec3::new(0.40,0.40,0.45), 10.0) };
+    let mat_water = Material { absorption: Vec3::new(0.45, 0.12, 0.06), ..material_from(tex_water, Vec3::new(0.85,0.95,1.0), 0.1, 0.2, 0.9, 0.05, 1.33, Vec3::new(0.92,0.98,1.0), Vec3::new(0.84,0.94,1.0), 12.0) };
+    // Tronco: corteza a los lados y anillos procedurales arriba/abajo (el bloque resuelve el atlas por cara)
+    let bark = material_from(tex_wood,  Vec3::new(1.0,1.0,1.0), 1.0, 0.10, 0.0, 0.0, 1.0, Vec3::new(0.60,0.40,0.20), Vec3::new(0.40,0.25,0.15), 6.0);
+    let rings = Arc::new(Texture::Noise(Arc::new(Noise {
+        space: Space::Uv,
+        scale: 6.0,
+        distortion: 3.0,
+        ramp: vec![(0.0, srgb(Vec3::new(0.78,0.6,0.38))), (0.7, srgb(Vec3::new(0.66,0.48,0.28))), (1.0, srgb(Vec3::new(0.45,0.3,0.15)))],
+        ..Noise::new(Pattern::Wood)
+    })));
+    let mat_wood = Material { texture: Texture::BlockAtlas { top: rings.clone(), side: Arc::new(bark.texture.clone()), bottom: rings }, ..bark };
+    let mat_leaf  = Material { alpha_cutoff: 0.5, ..material_from(tex_leaf,  Vec3::new(0.9,1.0,0.9), 1.0, 0.05, 0.0, 0.0, 1.0, Vec3::new(0.20,0.45,0.20), Vec3::new(0.15,0.35,0.15), 10.0) };
+    // Diamante: semitransparente con IOR alto y dispersión (Abbe ~55 es el real; bajo para que se note el fuego)
+    let mat_diamond = Material { abbe: 20.0, ..material_from(tex_diamond, Vec3::new(1.0,1.0,1.0), 0.5, 0.4, 0.5, 0.2, 2.42, Vec3::new(0.6,0.9,1.0), Vec3::new(0.4,0.7,0.9), 8.0) };
+    let mat_glowstone = Material {
+        albedo: srgb(Vec3::new(1.0, 0.9, 0.7)),
+        // Celdas de Voronoi: centros brillantes y bordes anaranjados (sin asset)
+        texture: Texture::Noise(Arc::new(Noise {
+            scale: 4.0,
+            ramp: vec![(0.0, srgb(Vec3::new(1.0, 0.9, 0.55))), (0.6, srgb(Vec3::new(0.95, 0.7, 0.3))), (1.0, srgb(Vec3::new(0.7, 0.4, 0.15)))],
+            ..Noise::new(Pattern::Voronoi)
+        })),
+        emission: Vec3::new(1.0, 0.75, 0.4),
+        emission_strength: 1.5,
+        ..Material::default()
+    };
     let mat_tnt = material_from(tex_tnt, Vec3::new(1.0,1.0,1.0), 0.9, 0.1, 0.0, 0.0, 1.0, Vec3::new(0.9,0.3,0.3), Vec3::new(0.7,0.15,0.15), 8.0);
 
     // === Escena (sin plano; isla flotante) ===
-    let mut scene = scene::Scene::new(Vec3::new(-0.25, -1.0, -0.35));
+    let mut scene = scene::Scene::new();
+    scene.add_light(Light::sun(Vec3::new(-0.25, -1.0, -0.35)));
+    let mut world = VoxelGrid::new();
+    let grass   = world.add_material(mat_grass);
+    let dirt    = world.add_material(mat_dirt);
+    let stone   = world.add_material(mat_stone);
+    let water   = world.add_material(mat_water);
+    let wood    = world.add_material(mat_wood.clone());
+    let log_x   = world.add_block(Block::new(mat_wood).oriented(Orientation::axis(0)));
+    let leaf    = world.add_material(mat_leaf);
+    let diamond = world.add_material(mat_diamond);
+    let tnt     = world.add_material(mat_tnt);
+    let glowstone = world.add_material(mat_glowstone);
 
     // Heightmap 5x5 (capas por columna)
     let hmap: [[i32; 5]; 5] = [
@@ -109,25 +178,28 @@ fn main() {
             for layer in 0..h {
                 let gy = base_y + layer;
                 let is_top = layer == h - 1;
-                let mat = if is_top { mat_grass.clone() } else if layer == 0 { mat_stone.clone() } else { mat_dirt.clone() };
-                add_block(&mut scene, gxw, gy, gzw, mat);
+                let id = if is_top { grass } else if layer == 0 { stone } else { dirt };
+                add_block(&mut world, gxw, gy, gzw, id);
             }
         }
     }
 
     // Cascada (columna) + bloque fuente pegado a tierra
-    for gy in base_y..(base_y + 3) { add_block(&mut scene, -3, gy, -1, mat_water.clone()); }
-    add_block(&mut scene, -2, base_y + 2, -1, mat_water.clone()); // “source” tocando la meseta
+    for gy in base_y..(base_y + 3) { add_block(&mut world, -3, gy, -1, water); }
+    add_block(&mut world, -2, base_y + 2, -1, water); // “source” tocando la meseta
 
     // Árbol en esquina (frontal derecha: gx=2, gz=0)
     let tree_gx = 2;
     let tree_gz = 0;
     let tree_col_h = hmap[(tree_gz + 2) as usize][(tree_gx + 2) as usize];
     let tree_top_y = base_y + tree_col_h - 1;
-    for i in 1..=3 { add_block(&mut scene, tree_gx, tree_top_y + i, tree_gz, mat_wood.clone()); }
+    for i in 1..=3 { add_block(&mut world, tree_gx, tree_top_y + i, tree_gz, wood); }
     let crown_y = tree_top_y + 3;
-    for dz in -1..=1 { for dx in -1..=1 { add_block(&mut scene, tree_gx + dx, crown_y, tree_gz + dz, mat_leaf.clone()); } }
-    add_block(&mut scene, tree_gx, crown_y + 1, tree_gz, mat_leaf.clone());
+    for dz in -1..=1 { for dx in -1..=1 { add_block(&mut world, tree_gx + dx, crown_y, tree_gz + dz, leaf); } }
+    add_block(&mut world, tree_gx, crown_y + 1, tree_gz, leaf);
+
+    // Tronco caído (acostado sobre X) en la esquina frontal izquierda
+    add_block(&mut world, -2, base_y + 1, 2, log_x);
 
     // === Mini cueva colgante bajo la isla ===
     // Caja de 3×2×3 (x,z,y) justo bajo la isla, con 2 aperturas.
@@ -159,33 +231,101 @@ fn main() {
 
                 if front_open || side_open || diamond_hole { continue; }
 
-                add_block(&mut scene, gx, gy, gz, mat_stone.clone());
+                add_block(&mut world, gx, gy, gz, stone);
             }
         }
     }
 
-    // Suelo (loseta completa en el nivel inferior)
+    // Suelo (loseta completa en el nivel inferior) de glowstone: ilumina la cueva
     for gz in (z0+1)..=(z1-1) {
         for gx in (x0+1)..=(x1-1) {
-            add_block(&mut scene, gx, top - (height - 1), gz, mat_stone.clone());
+            add_block(&mut world, gx, top - (height - 1), gz, glowstone);
         }
     }
 
     // TNT en la entrada frontal (visible)
-    add_block(&mut scene, 0, top - 1, z0, mat_tnt.clone());
+    add_block(&mut world, 0, top - 1, z0, tnt);
 
     // Diamantes en los huecos de la pared trasera (ahora SIN piedra detrás)
-    add_block(&mut scene, diamond_slot_a.0, top - 1, diamond_slot_a.1, mat_diamond.clone());
-    add_block(&mut scene, diamond_slot_b.0, top - 1, diamond_slot_b.1, mat_diamond.clone());
+    add_block(&mut world, diamond_slot_a.0, top - 1, diamond_slot_a.1, diamond);
+    add_block(&mut world, diamond_slot_b.0, top - 1, diamond_slot_b.1, diamond);
+
+    scene.add(Box::new(world));
+
+    // Antorchas en las aperturas de la cueva (frontal y ventana lateral)
+    let torch = Vec3::new(1.0, 0.6, 0.25);
+    scene.add_light(Light::Point { pos: Vec3::new(1.5, 0.5, 0.5), color: torch, intensity: 0.6 });
+    scene.add_light(Light::Point { pos: Vec3::new(-0.5, 0.5, 1.5), color: torch, intensity: 0.6 });
 
     // Cámara
-    let mut cam = OrbitCam { target: Vec3::new(0.0, base_y as f32 + 2.0, 0.0), yaw: 0.9, pitch: -0.50, dist: 9.2, fov_deg: 60.0 };
+    let cam = OrbitCam { target: Vec3::new(0.0, base_y as f32 + 2.0, 0.0), yaw: 0.9, pitch: -0.50, dist: 9.2, fov_deg: 60.0 };
+
+    (scene, cam)
+}
+
+// ==========================================================
+// Main
+// ==========================================================
+fn main() {
+    let command = match cli::parse(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => { eprintln!("error: {e}\n\n{}", cli::USAGE); std::process::exit(2); }
+    };
+    match command {
+        Command::Viewer(opts) => run_viewer(&opts),
+        Command::Render(opts) => run_headless(&opts),
+        Command::Help => println!("{}", cli::USAGE),
+    }
+}
+
+// Render offline sin ventana: acumula `samples` frames y guarda PNG/PPM
+fn run_headless(opts: &RenderOpts) {
+    let (scene, cam, sky) = load_world(opts.scene.as_deref());
+    eprintln!("(render) escena: {} objetos, {} luces, {} emisores", scene.objects().len(), scene.lights().len(), scene.emitters().len());
+
+    let mut fb = FrameBuffer::new(opts.width, opts.height);
+    let mut accum = Accumulator::new();
+    let view = View { cam, world_angle: opts.world_angle, scene_revision: scene.revision(), env_samples: opts.env_samples };
+
+    let t0 = std::time::Instant::now();
+    for i in 0..opts.samples {
+        render_scene(&mut fb, &scene, &cam, opts.world_angle, sky.as_ref(), opts.env_samples, i as u64 + 1);
+        accum.accumulate(&mut fb, view);
+        eprint!("\r(render) {}x{} — muestra {}/{}", opts.width, opts.height, i + 1, opts.samples);
+    }
+    eprintln!(" ({:.1}s)", t0.elapsed().as_secs_f32());
+
+    if let Err(e) = fb.save(&opts.out, &opts.tonemap) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+    eprintln!("(render) guardado en {}", opts.out);
+}
+
+fn run_viewer(opts: &ViewerOpts) {
+    // Ventana de tamaño fijo; la resolución de render = ventana / escala
+    let (win_w, win_h) = (opts.width * opts.scale, opts.height * opts.scale);
+    let (mut rl, th) = raylib::init()
+        .size(win_w, win_h)
+        .title("Rust Raytracer — Isla flotante con cueva")
+        .build();
+    rl.set_target_fps(60);
+
+    // Framebuffer (float) + textura de destino
+    let mut scale = opts.scale;
+    let mut fb = FrameBuffer::new(opts.width, opts.height);
+    let mut accum = Accumulator::new();
+    let mut tex = rl.load_texture_from_image(&th, &Image::gen_image_color(fb.width, fb.height, Color::BLACK)).unwrap();
+
+    // Skybox + escena
+    let (scene, mut cam, sky) = load_world(opts.scene.as_deref());
 
     let mut autorotate = true;
     let mut world_angle = 0.0_f32;
 
     let mut env_on = true;
     let env_samples: u32 = 4;
+    let mut tonemap = opts.tonemap;
     let mut frame_id: u64 = 1;
 
     // Loop
@@ -205,25 +345,41 @@ fn main() {
 
         if rl.is_key_pressed(KeyboardKey::KEY_F) { env_on = !env_on; }
 
+        // Tonemapping: solo cambia la conversión final, no reinicia la acumulación
+        if rl.is_key_pressed(KeyboardKey::KEY_T) { tonemap.op = tonemap.op.next(); }
+        if rl.is_key_pressed(KeyboardKey::KEY_COMMA)  { tonemap.exposure -= 0.5; }
+        if rl.is_key_pressed(KeyboardKey::KEY_PERIOD) { tonemap.exposure += 0.5; }
+
+        // [ / ] = bajar/subir la escala de ampliación (recrea framebuffer y textura)
+        let mut new_scale = scale;
+        if rl.is_key_pressed(KeyboardKey::KEY_LEFT_BRACKET)  { new_scale -= 1; }
+        if rl.is_key_pressed(KeyboardKey::KEY_RIGHT_BRACKET) { new_scale += 1; }
+        let new_scale = new_scale.clamp(1, cli::MAX_SCALE);
+        if new_scale != scale {
+            scale = new_scale;
+            fb = FrameBuffer::new((win_w / scale).max(1), (win_h / scale).max(1));
+            tex = rl.load_texture_from_image(&th, &Image::gen_image_color(fb.width, fb.height, Color::BLACK)).unwrap();
+        }
+
         let samples = if env_on { env_samples } else { 0 };
-        render_scene(&mut image, &scene, &cam, world_angle, sky.as_ref(), samples, frame_id);
+        render_scene(&mut fb, &scene, &cam, world_angle, sky.as_ref(), samples, frame_id);
         frame_id = frame_id.wrapping_add(1);
 
+        // Promedio progresivo mientras cámara/ángulo/escena no cambien
+        let view = View { cam, world_angle, scene_revision: scene.revision(), env_samples: samples };
+        accum.accumulate(&mut fb, view);
+
         // Subir y dibujar
-        {
-            let colors = image.get_image_data();
-            let slice: &[Color] = colors.as_ref().as_ref();
-            let mut bytes = Vec::<u8>::with_capacity((W * H * 4) as usize);
-            for c in slice.iter() { bytes.push(c.r); bytes.push(c.g); bytes.push(c.b); bytes.push(c.a); }
-            tex.update_texture(&bytes).expect("update_texture failed");
-        }
+        tex.update_texture(&fb.to_rgba8(&tonemap)).expect("update_texture failed");
         let mut d = rl.begin_drawing(&th);
         d.clear_background(Color::BLACK);
         d.draw_texture_pro(&tex,
-            Rectangle { x:0.0, y:0.0, width: W as f32, height: H as f32 },
-            Rectangle { x:0.0, y:0.0, width: (W*SCALE) as f32, height: (H*SCALE) as f32 },
+            Rectangle { x:0.0, y:0.0, width: fb.width as f32, height: fb.height as f32 },
+            Rectangle { x:0.0, y:0.0, width: win_w as f32, height: win_h as f32 },
             Vector2::zero(), 0.0, Color::WHITE
         );
         d.draw_text("R=rotación | F=IBL on/off | Isla flotante con cueva", 8, 8, 16, Color::RAYWHITE);
+        d.draw_text(&format!("{}x{} (x{}) [ ]=escala | frames acumulados: {}", fb.width, fb.height, scale, accum.frames()), 8, 28, 16, Color::RAYWHITE);
+        d.draw_text(&format!("T=tonemap: {} | , .=exposición: {:+.1} EV", tonemap.op.name(), tonemap.exposure), 8, 48, 16, Color::RAYWHITE);
     }
 }
@@ -1,16 +1,38 @@
 use crate::math::Vec3;
-use crate::texture::Texture;
+use crate::texture::{Texture, Footprint};
+
+/// Modelo de shading: el clásico Lambert+Phong (kd/specular) o metallic-roughness (GGX).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ShadingModel {
+   #[default]
+   Legacy,
+   MetallicRoughness,
+}
+
+/// Reflectancia dieléctrica estándar del modelo metallic-roughness (F0 = 0.04). El default de
+/// `specular` es el brillo Phong, así que los loaders la ponen al pasar un material a GGX.
+pub const DIELECTRIC_SPECULAR: f32 = 0.5;
 
 // Material no es Copy porque guarda Texture
 #[derive(Debug,Clone)]
 pub struct Material {
-   pub albedo: Vec3,
-   pub kd: f32,          // coeficiente difuso (Lambert)
-   pub specular: f32,    // brillo especular
+   pub albedo: Vec3,     // color base (× textura)
+   pub kd: f32,          // coeficiente difuso (Lambert) — solo Legacy
+   pub specular: f32,    // Legacy: brillo Phong; MetallicRoughness: reflectancia dieléctrica (0.5 ≈ F0 0.04, ver DIELECTRIC_SPECULAR)
    pub transparency: f32,
-   pub reflectivity: f32,
+   pub reflectivity: f32, // solo Legacy; en MetallicRoughness la reflexión sale de Fresnel
    pub ior: f32,
+   pub abbe: f32,             // número de Abbe (dispersión); 0 = sin dispersión
+   pub absorption: Vec3,      // Beer–Lambert: coeficiente por unidad de distancia dentro del medio
    pub texture: Texture,
+   pub normal_map: Texture,   // normales en espacio tangente (RGB → [-1,1]); Texture::None = sin mapa
+   pub normal_strength: f32,
+   pub model: ShadingModel,
+   pub metallic: f32,
+   pub roughness: f32,
+   pub emission: Vec3,        // color emitido (× textura); negro = no emite
+   pub emission_strength: f32,
+   pub alpha_cutoff: f32,     // > 0: los texels con alfa menor son agujeros (hojas, follaje)
 }
 
 impl Default for Material {
@@ -22,23 +44,72 @@ impl Default for Material {
          transparency: 0.0,
          reflectivity: 0.0,
          ior: 1.5,
+         abbe: 0.0,
+         absorption: Vec3::new(0.0, 0.0, 0.0),
          texture: Texture::None,
+         normal_map: Texture::None,
+         normal_strength: 1.0,
+         model: ShadingModel::Legacy,
+         metallic: 0.0,
+         roughness: 0.5,
+         emission: Vec3::new(0.0, 0.0, 0.0),
+         emission_strength: 1.0,
+         alpha_cutoff: 0.0,
+      }
+   }
+}
+
+impl Material {
+   pub fn is_dispersive(&self) -> bool { self.abbe > 0.0 }
+
+   /// IOR a la longitud de onda de la muestra (o el nominal si aún no se eligió una).
+   pub fn ior_at(&self, wavelength: Option<f32>) -> f32 {
+      match wavelength {
+         Some(l) if self.is_dispersive() => crate::spectrum::cauchy_ior(self.ior, self.abbe, l),
+         _ => self.ior,
       }
    }
+
+   /// Radiancia emitida sin textura (la que usan las luces de área).
+   pub fn emitted(&self) -> Vec3 { self.emission.mul(self.emission_strength) }
+
+   /// Normal de shading: la geométrica perturbada por el normal map (si hay).
+   pub fn shading_normal(&self, hit: &Hit, footprint: Footprint) -> Vec3 {
+      let n = hit.n.normalize();
+      if matches!(self.normal_map, Texture::None) { return n; }
+      let c = self.normal_map.sample_with_normal(hit.uv, hit.p, n, footprint);
+      let m = Vec3::new((2.0 * c.x - 1.0) * self.normal_strength, (2.0 * c.y - 1.0) * self.normal_strength, 2.0 * c.z - 1.0);
+      // Base ortonormal (Gram-Schmidt) alrededor de la normal geométrica
+      let t = hit.tangent.sub(n.mul(n.dot(hit.tangent))).normalize();
+      let b = hit.bitangent.sub(n.mul(n.dot(hit.bitangent))).sub(t.mul(t.dot(hit.bitangent))).normalize();
+      let ns = t.mul(m.x).add(b.mul(m.y)).add(n.mul(m.z.max(0.0))).normalize();
+      if ns.length() > 0.0 && ns.dot(n) > 0.0 { ns } else { n }
+   }
+
+   /// ¿El impacto cae en un texel recortado? El rayo debe seguir de largo.
+   pub fn is_cutout(&self, uv: (f32, f32), n: Vec3) -> bool {
+      self.alpha_cutoff > 0.0 && self.texture.alpha_with_normal(uv, n) < self.alpha_cutoff
+   }
+
+   pub fn is_emissive(&self) -> bool {
+      let e = self.emitted();
+      e.x > 0.0 || e.y > 0.0 || e.z > 0.0
+   }
 }
 
-// Puntero solo-lectura al material para mantener Hit como Copy
+// Referencia prestada al material de la forma: Hit sigue siendo Copy y no vive más que la escena
 #[derive(Copy, Clone, Debug)]
-pub struct Hit {
+pub struct Hit<'a> {
    pub t: f32,
    pub p: Vec3,
    pub n: Vec3,
-   pub mat_ptr: *const Material,
+   pub mat: &'a Material,
    pub uv: (f32, f32),
+   pub tangent: Vec3,   // dirección de +u en mundo (para normal mapping)
+   pub bitangent: Vec3, // dirección de +v en mundo
+   pub uv_density: f32, // unidades de UV por unidad de mundo (escala el footprint de las texturas)
 }
 
-impl Hit {
-   pub fn material(&self) -> &Material {
-      unsafe { &*self.mat_ptr }
-   }
+impl<'a> Hit<'a> {
+   pub fn material(&self) -> &'a Material { self.mat }
 }
@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Vec3 { pub x: f32, pub y: f32, pub z: f32 }
 
 impl Vec3 {
@@ -11,6 +11,10 @@ impl Vec3 {
    pub fn cross(self, o: Vec3) -> Vec3 {
       Vec3::new(self.y*o.z - self.z*o.y, self.z*o.x - self.x*o.z, self.x*o.y - self.y*o.x)
    }
+   pub fn min(self, o: Vec3) -> Vec3 { Vec3::new(self.x.min(o.x), self.y.min(o.y), self.z.min(o.z)) }
+   pub fn max(self, o: Vec3) -> Vec3 { Vec3::new(self.x.max(o.x), self.y.max(o.y), self.z.max(o.z)) }
+   /// Componente por índice (0=x, 1=y, 2=z)
+   pub fn axis(self, i: usize) -> f32 { match i { 0 => self.x, 1 => self.y, _ => self.z } }
    pub fn length(self) -> f32 { self.dot(self).sqrt() }
    pub fn normalize(self) -> Vec3 { let l=self.length(); if l>0.0 { self.mul(1.0/l) } else { self } }
    pub fn clamp01(self) -> Vec3 {
@@ -0,0 +1,204 @@
+// ==========================================================
+// Ruido procedural: Perlin, simplex, fBm, turbulencia, mármol, madera y Voronoi
+// ==========================================================
+// Todo es determinista (hash entero de las coordenadas de celda, sin tablas ni RNG),
+// así que el mismo punto da el mismo valor en todos los hilos y frames.
+use crate::math::Vec3;
+
+/// Patrón evaluado por una textura `Noise`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+   Perlin,     // ruido de gradiente clásico
+   Simplex,    // ruido simplex 3D (menos artefactos de grilla)
+   Fbm,        // suma de octavas de Perlin
+   Turbulence, // suma de |Perlin| (vetas marcadas)
+   Marble,     // sin(x + turbulencia)
+   Wood,       // anillos alrededor del eje Y perturbados con fBm
+   Voronoi,    // distancia al punto característico más cercano (celdas)
+}
+
+/// Dónde se evalúa el ruido: en el punto de impacto (sólido 3D) o en las UV de la superficie
+/// (centradas y llevadas al plano XZ, así la madera dibuja anillos en la cara).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Space {
+   #[default]
+   World,
+   Uv,
+}
+
+/// Textura procedural: patrón + escala + rampa de color.
+#[derive(Debug, Clone)]
+pub struct Noise {
+   pub pattern: Pattern,
+   pub space: Space,
+   pub scale: f32,            // frecuencia base (celdas por unidad)
+   pub octaves: u32,          // fBm / turbulencia / mármol / madera
+   pub distortion: f32,       // cuánto perturba la turbulencia al mármol y la madera
+   pub ramp: Vec<(f32, Vec3)>, // paradas (t, color) ordenadas por t
+}
+
+impl Noise {
+   pub fn new(pattern: Pattern) -> Self {
+      Self {
+         pattern,
+         space: Space::World,
+         scale: 1.0,
+         octaves: 5,
+         distortion: 4.0,
+         ramp: vec![(0.0, Vec3::new(0.0, 0.0, 0.0)), (1.0, Vec3::new(1.0, 1.0, 1.0))],
+      }
+   }
+
+   /// Color en el punto `p` (mundo) con coordenadas `uv`; `footprint` descarta las
+   /// octavas más finas que un píxel para que el ruido no haga aliasing a lo lejos.
+   pub fn sample(&self, uv: (f32, f32), p: Vec3, footprint: f32) -> Vec3 {
+      let q = match self.space { Space::World => p, Space::Uv => Vec3::new(uv.0 - 0.5, 0.0, uv.1 - 0.5) }.mul(self.scale);
+      let octaves = self.octaves_for(footprint * self.scale);
+      let t = match self.pattern {
+         Pattern::Perlin => 0.5 + 0.5 * perlin(q),
+         Pattern::Simplex => 0.5 + 0.5 * simplex(q),
+         Pattern::Fbm => 0.5 + 0.5 * fbm(q, octaves),
+         Pattern::Turbulence => turbulence(q, octaves),
+         Pattern::Marble => 0.5 + 0.5 * (q.x * std::f32::consts::PI + self.distortion * turbulence(q, octaves)).sin(),
+         Pattern::Wood => {
+            let r = (q.x * q.x + q.z * q.z).sqrt() + 0.1 * self.distortion * fbm(q, octaves);
+            r - r.floor()
+         }
+         Pattern::Voronoi => voronoi(q),
+      };
+      self.ramp_at(t.clamp(0.0, 1.0))
+   }
+
+   // Octavas cuya frecuencia (2^i) aún es más grande que medio píxel; al menos una
+   fn octaves_for(&self, footprint: f32) -> u32 {
+      if footprint <= 0.0 { return self.octaves.max(1); }
+      let limit = (0.5 / footprint).log2().floor().max(0.0) as u32 + 1;
+      self.octaves.min(limit).max(1)
+   }
+
+   fn ramp_at(&self, t: f32) -> Vec3 {
+      let Some(&(t0, c0)) = self.ramp.first() else { return Vec3::new(t, t, t) };
+      if t <= t0 { return c0; }
+      for w in self.ramp.windows(2) {
+         let ((ta, ca), (tb, cb)) = (w[0], w[1]);
+         if t <= tb {
+            let k = if tb > ta { (t - ta) / (tb - ta) } else { 1.0 };
+            return ca.mul(1.0 - k).add(cb.mul(k));
+         }
+      }
+      self.ramp.last().unwrap().1
+   }
+}
+
+// Hash entero de una celda (mezcla tipo murmur), da 32 bits bien repartidos
+fn hash(x: i32, y: i32, z: i32) -> u32 {
+   let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841) ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+   h ^= h >> 16;
+   h = h.wrapping_mul(0x7feb_352d);
+   h ^= h >> 15;
+   h = h.wrapping_mul(0x846c_a68b);
+   h ^ (h >> 16)
+}
+
+// Uno de los 12 gradientes de Perlin (aristas del cubo) elegido por hash
+fn grad(h: u32, x: f32, y: f32, z: f32) -> f32 {
+   match h % 12 {
+      0 => x + y, 1 => -x + y, 2 => x - y, 3 => -x - y,
+      4 => x + z, 5 => -x + z, 6 => x - z, 7 => -x - z,
+      8 => y + z, 9 => -y + z, 10 => y - z, _ => -y - z,
+   }
+}
+
+fn fade(t: f32) -> f32 { t * t * t * (t * (t * 6.0 - 15.0) + 10.0) }
+fn lerp(a: f32, b: f32, t: f32) -> f32 { a + (b - a) * t }
+
+/// Ruido de Perlin (versión "improved") en [-1, 1] aprox.
+pub fn perlin(p: Vec3) -> f32 {
+   let (xf, yf, zf) = (p.x.floor(), p.y.floor(), p.z.floor());
+   let (x, y, z) = (xf as i32, yf as i32, zf as i32);
+   let (fx, fy, fz) = (p.x - xf, p.y - yf, p.z - zf);
+   let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+   let g = |dx: i32, dy: i32, dz: i32| grad(hash(x + dx, y + dy, z + dz), fx - dx as f32, fy - dy as f32, fz - dz as f32);
+   lerp(
+      lerp(lerp(g(0, 0, 0), g(1, 0, 0), u), lerp(g(0, 1, 0), g(1, 1, 0), u), v),
+      lerp(lerp(g(0, 0, 1), g(1, 0, 1), u), lerp(g(0, 1, 1), g(1, 1, 1), u), v),
+      w,
+   )
+}
+
+/// Ruido simplex 3D (Gustavson) en [-1, 1] aprox.
+pub fn simplex(p: Vec3) -> f32 {
+   const F3: f32 = 1.0 / 3.0;
+   const G3: f32 = 1.0 / 6.0;
+   // Sesgo a la grilla de tetraedros
+   let s = (p.x + p.y + p.z) * F3;
+   let (i, j, k) = ((p.x + s).floor(), (p.y + s).floor(), (p.z + s).floor());
+   let t = (i + j + k) * G3;
+   let x0 = Vec3::new(p.x - (i - t), p.y - (j - t), p.z - (k - t));
+
+   // Qué tetraedro del cubo: orden de las componentes de x0
+   let (o1, o2) = if x0.x >= x0.y {
+      if x0.y >= x0.z { ((1, 0, 0), (1, 1, 0)) }
+      else if x0.x >= x0.z { ((1, 0, 0), (1, 0, 1)) }
+      else { ((0, 0, 1), (1, 0, 1)) }
+   } else if x0.y < x0.z { ((0, 0, 1), (0, 1, 1)) }
+   else if x0.x < x0.z { ((0, 1, 0), (0, 1, 1)) }
+   else { ((0, 1, 0), (1, 1, 0)) };
+
+   let (i, j, k) = (i as i32, j as i32, k as i32);
+   let corners = [(0, 0, 0), o1, o2, (1, 1, 1)];
+   let mut sum = 0.0;
+   for (c, &(a, b, d)) in corners.iter().enumerate() {
+      let off = c as f32 * G3;
+      let x = Vec3::new(x0.x - a as f32 + off, x0.y - b as f32 + off, x0.z - d as f32 + off);
+      let r = 0.6 - x.dot(x);
+      if r > 0.0 {
+         let r2 = r * r;
+         sum += r2 * r2 * grad(hash(i + a, j + b, k + d), x.x, x.y, x.z);
+      }
+   }
+   32.0 * sum
+}
+
+/// fBm: octavas de Perlin con frecuencia ×2 y amplitud ×½, normalizado a [-1, 1] aprox.
+pub fn fbm(p: Vec3, octaves: u32) -> f32 {
+   let (mut sum, mut amp, mut norm, mut q) = (0.0, 1.0, 0.0, p);
+   for _ in 0..octaves {
+      sum += amp * perlin(q);
+      norm += amp;
+      amp *= 0.5;
+      q = q.mul(2.0);
+   }
+   if norm > 0.0 { sum / norm } else { 0.0 }
+}
+
+/// Turbulencia: como fBm pero con |ruido|, en [0, 1] aprox.
+pub fn turbulence(p: Vec3, octaves: u32) -> f32 {
+   let (mut sum, mut amp, mut norm, mut q) = (0.0, 1.0, 0.0, p);
+   for _ in 0..octaves {
+      sum += amp * perlin(q).abs();
+      norm += amp;
+      amp *= 0.5;
+      q = q.mul(2.0);
+   }
+   if norm > 0.0 { (sum / norm * 2.0).min(1.0) } else { 0.0 }
+}
+
+/// Voronoi (F1): distancia al punto característico más cercano, un punto por celda.
+pub fn voronoi(p: Vec3) -> f32 {
+   let (cx, cy, cz) = (p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32);
+   let mut best = f32::INFINITY;
+   for dz in -1..=1 {
+      for dy in -1..=1 {
+         for dx in -1..=1 {
+            let (x, y, z) = (cx + dx, cy + dy, cz + dz);
+            let h = hash(x, y, z);
+            // Tres coordenadas de 10 bits del mismo hash → punto dentro de la celda
+            let r = |s: u32| ((h >> s) & 1023) as f32 / 1023.0;
+            let f = Vec3::new(x as f32 + r(0), y as f32 + r(10), z as f32 + r(20));
+            best = best.min(f.sub(p).length());
+         }
+      }
+   }
+   best
+}
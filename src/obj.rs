@@ -0,0 +1,183 @@
+// ==========================================================
+// Importador Wavefront OBJ + MTL
+// ==========================================================
+// Soporta v / vt / vn / f (polígonos en abanico, índices negativos), usemtl y mtllib.
+// MTL: Kd, Ks, Ke, d / Tr, Tf, Ni, illum, map_Kd, map_d (recorte por alfa) y la extensión PBR Pm / Pr / norm, mapeados a `material::Material`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use crate::{math::Vec3, material::{Material, ShadingModel, DIELECTRIC_SPECULAR}, texture::Texture};
+use crate::shapes::{TriangleMesh, mesh::Triangle};
+
+pub fn load_obj(path: &str) -> Result<TriangleMesh, String> {
+   let src = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+   let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+   let mut positions = Vec::new();
+   let mut normals = Vec::new();
+   let mut uvs = Vec::new();
+   let mut triangles = Vec::new();
+   let mut materials: Vec<Material> = Vec::new();
+   let mut mat_index: HashMap<String, u32> = HashMap::new();
+   let mut library: HashMap<String, Material> = HashMap::new();
+   let mut current: u32 = 0;
+
+   for (i, raw) in src.lines().enumerate() {
+      let err = |msg: String| format!("{path}:{}: {msg}", i + 1);
+      let line = raw.split('#').next().unwrap_or("").trim();
+      let mut it = line.split_whitespace();
+      let Some(tag) = it.next() else { continue; };
+      let rest: Vec<&str> = it.collect();
+      match tag {
+         "v" => positions.push(floats3(&rest).map_err(err)?),
+         "vn" => normals.push(floats3(&rest).map_err(err)?),
+         "vt" => {
+            let u = rest.first().ok_or_else(|| err("vt sin coordenadas".into()))?;
+            let v = rest.get(1).unwrap_or(&"0");
+            uvs.push((float(u).map_err(err)?, float(v).map_err(err)?));
+         }
+         "f" => {
+            if materials.is_empty() { materials.push(Material::default()); }
+            let corners: Vec<(u32, Option<u32>, Option<u32>)> = rest.iter()
+               .map(|c| corner(c, positions.len(), uvs.len(), normals.len()))
+               .collect::<Result<_, _>>().map_err(err)?;
+            if corners.len() < 3 { return Err(err("cara con menos de 3 vértices".into())); }
+            // Triangulación en abanico
+            for k in 1..corners.len() - 1 {
+               let c = [corners[0], corners[k], corners[k + 1]];
+               let all_uv = c.iter().all(|v| v.1.is_some());
+               let all_n = c.iter().all(|v| v.2.is_some());
+               triangles.push(Triangle {
+                  p: c.map(|v| v.0),
+                  uv: if all_uv { Some(c.map(|v| v.1.unwrap())) } else { None },
+                  n: if all_n { Some(c.map(|v| v.2.unwrap())) } else { None },
+                  mat: current,
+               });
+            }
+         }
+         "mtllib" => {
+            for lib in &rest {
+               let lib_path = dir.join(lib);
+               match load_mtl(&lib_path) {
+                  Ok(mats) => library.extend(mats),
+                  Err(e) => eprintln!("(info) {e} — se usan materiales por defecto"),
+               }
+            }
+         }
+         "usemtl" => {
+            let name = rest.first().copied().unwrap_or("");
+            current = *mat_index.entry(name.to_string()).or_insert_with(|| {
+               materials.push(library.get(name).cloned().unwrap_or_default());
+               (materials.len() - 1) as u32
+            });
+         }
+         _ => {} // o / g / s / l: ignorados
+      }
+   }
+
+   if triangles.is_empty() { return Err(format!("{path}: la malla no tiene caras")); }
+   Ok(TriangleMesh::new(positions, normals, uvs, triangles, materials))
+}
+
+fn load_mtl(path: &Path) -> Result<HashMap<String, Material>, String> {
+   let shown = path.display();
+   let src = std::fs::read_to_string(path).map_err(|e| format!("{shown}: {e}"))?;
+   let dir = path.parent().unwrap_or(Path::new(""));
+   let mut mats: HashMap<String, Material> = HashMap::new();
+   let mut name: Option<String> = None;
+   let mut reflective: HashSet<String> = HashSet::new();
+
+   for (i, raw) in src.lines().enumerate() {
+      let err = |msg: String| format!("{shown}:{}: {msg}", i + 1);
+      let line = raw.split('#').next().unwrap_or("").trim();
+      let mut it = line.split_whitespace();
+      let Some(tag) = it.next() else { continue; };
+      let rest: Vec<&str> = it.collect();
+
+      if tag == "newmtl" {
+         let n = rest.first().ok_or_else(|| err("newmtl sin nombre".into()))?.to_string();
+         mats.insert(n.clone(), Material { kd: 1.0, ior: 1.0, ..Material::default() });
+         name = Some(n);
+         continue;
+      }
+      let Some(m) = name.as_ref().and_then(|n| mats.get_mut(n)) else { continue; };
+      match tag {
+         "Kd" => m.albedo = floats3(&rest).map_err(err)?,
+         // Filtro de transmisión: color que queda tras una unidad de distancia → absorción
+         "Tf" => {
+            let tf = floats3(&rest).map_err(err)?;
+            let a = |c: f32| -c.clamp(1e-4, 1.0).ln();
+            m.absorption = Vec3::new(a(tf.x), a(tf.y), a(tf.z));
+         }
+         "Ke" => m.emission = floats3(&rest).map_err(err)?,
+         "Ks" => {
+            let ks = floats3(&rest).map_err(err)?;
+            m.specular = (ks.x + ks.y + ks.z) / 3.0;
+         }
+         "d" => m.transparency = 1.0 - float(rest.first().unwrap_or(&"1")).map_err(err)?,
+         "Tr" => m.transparency = float(rest.first().unwrap_or(&"0")).map_err(err)?,
+         "Ni" => m.ior = float(rest.first().unwrap_or(&"1")).map_err(err)?,
+         // Extensión PBR de MTL: activa el modelo metallic-roughness
+         "Pm" => { m.metallic = float(rest.first().unwrap_or(&"0")).map_err(err)?; m.model = ShadingModel::MetallicRoughness; }
+         "Pr" => { m.roughness = float(rest.first().unwrap_or(&"0.5")).map_err(err)?; m.model = ShadingModel::MetallicRoughness; }
+         "illum" => {
+            // 3/5/7: reflexión por raytracing (intensidad = Ks, se aplica al final)
+            if matches!(rest.first().copied(), Some("3" | "5" | "7")) { reflective.insert(name.clone().unwrap()); }
+         }
+         "map_Kd" => {
+            // La ruta es el último token (ignora opciones tipo -s / -o)
+            if let Some(file) = rest.last() {
+               let tex_path = dir.join(file);
+               match Texture::from_file(&tex_path.to_string_lossy()) {
+                  Some(tex) => m.texture = tex,
+                  None => eprintln!("(info) No se encontró {} — sin textura.", tex_path.display()),
+               }
+            }
+         }
+         // Normal map en espacio tangente (extensión PBR de MTL)
+         "norm" => {
+            if let Some(file) = rest.last() {
+               let tex_path = dir.join(file);
+               match Texture::from_file(&tex_path.to_string_lossy()) {
+                  Some(tex) => m.normal_map = tex.as_data(),
+                  None => eprintln!("(info) No se encontró {} — sin normal map.", tex_path.display()),
+               }
+            }
+         }
+         // Recorte por alfa: se usa el canal alfa de map_Kd
+         "map_d" => m.alpha_cutoff = 0.5,
+         _ => {} // Ka, Ns, etc.: sin equivalente en el modelo actual
+      }
+   }
+   for n in &reflective {
+      if let Some(m) = mats.get_mut(n) { m.reflectivity = m.specular; }
+   }
+   // Ks es brillo Phong: los materiales PBR usan la reflectancia dieléctrica estándar
+   for m in mats.values_mut().filter(|m| m.model == ShadingModel::MetallicRoughness) { m.specular = DIELECTRIC_SPECULAR; }
+   Ok(mats)
+}
+
+// --- Helpers de parseo ---
+fn float(s: &str) -> Result<f32, String> { s.parse().map_err(|_| format!("número inválido: {s}")) }
+
+fn floats3(t: &[&str]) -> Result<Vec3, String> {
+   if t.len() < 3 { return Err("se esperaban 3 componentes".into()); }
+   Ok(Vec3::new(float(t[0])?, float(t[1])?, float(t[2])?))
+}
+
+// Resuelve un índice OBJ (base 1, negativos relativos al final)
+fn index(s: &str, count: usize) -> Result<u32, String> {
+   let i: i64 = s.parse().map_err(|_| format!("índice inválido: {s}"))?;
+   let resolved = if i < 0 { count as i64 + i } else { i - 1 };
+   if resolved < 0 || resolved >= count as i64 { return Err(format!("índice fuera de rango: {s}")); }
+   Ok(resolved as u32)
+}
+
+// Esquina de cara: v, v/vt, v//vn o v/vt/vn
+fn corner(c: &str, np: usize, nt: usize, nn: usize) -> Result<(u32, Option<u32>, Option<u32>), String> {
+   let mut parts = c.split('/');
+   let p = index(parts.next().unwrap_or(""), np)?;
+   let t = match parts.next() { Some(s) if !s.is_empty() => Some(index(s, nt)?), _ => None };
+   let n = match parts.next() { Some(s) if !s.is_empty() => Some(index(s, nn)?), _ => None };
+   Ok((p, t, n))
+}
@@ -1,130 +1,242 @@
-use crate::{math::Vec3, ray::Ray, material::Hit, scene::Scene, camera::OrbitCam};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use crate::{math::Vec3, ray::Ray, material::{Hit, ShadingModel}, scene::Scene, camera::OrbitCam};
 use crate::skybox::Skybox;
 use crate::lighting::{self, Rng};
-use raylib::prelude::*;
+use crate::{brdf, spectrum};
+use crate::light::{Light, Incident};
+use crate::framebuffer::FrameBuffer;
+use crate::texture::Footprint;
 
-pub const W: i32 = 320;
-pub const H: i32 = 180;
-pub const SCALE: i32 = 4;
+const TILE: i32 = 16;
 
 fn sky_fallback(dir: Vec3) -> Vec3 {
    let t = 0.5 * (dir.y + 1.0);
-   let top = Vec3::new(0.5, 0.7, 1.0);
+   let top = Vec3::new(0.214, 0.448, 1.0); // (0.5, 0.7, 1.0) en sRGB, pasado a lineal
    let bottom = Vec3::new(1.0, 1.0, 1.0);
    bottom.mul(1.0 - t).add(top.mul(t))
 }
 
-fn in_shadow(scene: &Scene, p: Vec3, n: Vec3, light_dir: Vec3) -> bool {
+fn in_shadow(scene: &Scene, p: Vec3, n: Vec3, dir_to_light: Vec3, max_dist: f32) -> bool {
    let bias = 5e-3;
    let origin = p.add(n.normalize().mul(bias));
-   let dir_to_light = light_dir.normalize().mul(-1.0);
    let shadow_ray = Ray { origin, dir: dir_to_light };
-   scene.trace(shadow_ray, 0.001, 1e9).is_some()
+   scene.trace(shadow_ray, 0.001, max_dist - bias).is_some()
 }
 
 fn miss_color(dir: Vec3, sky: Option<&Skybox>) -> Vec3 {
-   if let Some(sb) = sky { sb.sample_dir(dir).clamp01() } else { sky_fallback(dir).clamp01() }
+   if let Some(sb) = sky { sb.sample_dir(dir) } else { sky_fallback(dir) }
 }
 
-fn local_shade(scene: &Scene, hit: &Hit, light_dir: Vec3, sky: Option<&Skybox>, rng: &mut Rng, env_samples: u32) -> Vec3 {
+// Lo que no cambia durante un frame: escena, cielo, muestras de IBL y luces ya rotadas
+struct Ctx<'a> {
+   scene: &'a Scene,
+   sky: Option<&'a Skybox>,
+   env_samples: u32,
+   lights: &'a [Light],
+   pixel_angle: f32, // ángulo que cubre un píxel (rad): footprint = distancia × pixel_angle
+}
+
+// Transmitancia de Beer–Lambert tras recorrer `dist` dentro de un medio
+fn transmittance(absorption: Vec3, dist: f32) -> Vec3 {
+   Vec3::new((-absorption.x * dist).exp(), (-absorption.y * dist).exp(), (-absorption.z * dist).exp())
+}
+
+fn local_shade(ctx: &Ctx, hit: &Hit, view: Vec3, footprint: Footprint, rng: &mut Rng) -> Vec3 {
+   let Ctx { scene, sky, env_samples, lights, .. } = *ctx;
    let n = hit.n.normalize();
    let mat = hit.material();
 
    // *** AQUÍ el cambio: usar atlas top/side/bottom según la normal ***
-   let tex_color = mat.texture.sample_with_normal(hit.uv, n);
+   let tex_color = mat.texture.sample_with_normal(hit.uv, hit.p, n, footprint);
    let base = mat.albedo.hadamard(tex_color);
 
-   // Luz directa + sombras
-   let l = light_dir.normalize().mul(-1.0);
-   let mut direct = Vec3::new(0.0, 0.0, 0.0);
-   let ndotl = (n.dot(l)).max(0.0);
-   if ndotl > 0.0 && !in_shadow(scene, hit.p, n, light_dir) {
-      let diffuse_direct = base.mul(ndotl * mat.kd);
-      let r = crate::math::Vec3::reflect(l.mul(-1.0), n).normalize();
-      let view_dir = Vec3::new(0.0, 0.0, 1.0);
-      let spec = mat.specular * (r.dot(view_dir).max(0.0)).powf(32.0);
-      let specular = Vec3::new(spec, spec, spec);
-      direct = diffuse_direct.add(specular);
-   }
+   // Luces de área: un emisor al azar por muestra (peso × nº de emisores)
+   let emitters = scene.emitters();
+   let area = if emitters.is_empty() { None } else {
+      let k = ((rng.next_f32() * emitters.len() as f32) as usize).min(emitters.len() - 1);
+      emitters[k].sample(hit.p, rng).map(|inc| Incident { radiance: inc.radiance.mul(emitters.len() as f32), ..inc })
+   };
+
+   // Luces visibles desde el punto (rayo de sombra por luz); se recorren sin armar colecciones
+   let visible = lights.iter().filter_map(|light| light.incident(hit.p)).chain(area)
+      .filter(|inc| n.dot(inc.l) > 0.0 && !in_shadow(scene, hit.p, n, inc.l, inc.dist));
 
    // IBL difusa (bajita para que se note la sombra)
    let env = if let (Some(sb), s) = (sky, env_samples) { lighting::diffuse_env(n, sb, rng, s) } else { Vec3::new(0.0,0.0,0.0) };
-   let diffuse_env = base.hadamard(env).mul(0.25);
-   let ambient = base.mul(0.01);
 
-   direct.add(diffuse_env).add(ambient)
+   let emitted = mat.emitted().hadamard(tex_color);
+   let shaded = match mat.model {
+      ShadingModel::Legacy => {
+         let mut direct = Vec3::new(0.0, 0.0, 0.0);
+         for inc in visible {
+            let diffuse_direct = base.mul(n.dot(inc.l) * mat.kd);
+            let r = crate::math::Vec3::reflect(inc.l.mul(-1.0), n).normalize();
+            let spec = mat.specular * (r.dot(view).max(0.0)).powf(32.0);
+            direct = direct.add(diffuse_direct.add(Vec3::new(spec, spec, spec)).hadamard(inc.radiance));
+         }
+         direct.add(base.hadamard(env).mul(0.25)).add(base.mul(0.01))
+      }
+      ShadingModel::MetallicRoughness => {
+         // Lo especular del entorno lo aporta el rayo reflejado en trace_color
+         let surf = brdf::Surface::new(base, mat.metallic, mat.roughness, mat.specular);
+         let direct = visible.fold(Vec3::new(0.0, 0.0, 0.0), |acc, inc| acc.add(brdf::eval(&surf, n, view, inc.l).hadamard(inc.radiance)));
+         let diffuse = base.mul(1.0 - surf.metallic);
+         direct.add(diffuse.hadamard(env).mul(0.25)).add(diffuse.mul(0.01))
+      }
+   };
+   shaded.add(emitted)
 }
 
-fn trace_color(scene: &Scene, ray: Ray, depth: u32, sky: Option<&Skybox>, env_samples: u32, light_dir: Vec3, rng: &mut Rng) -> Vec3 {
+/// `absorption`: coeficiente del medio por el que viaja el rayo (cero en el aire).
+/// `wavelength`: λ (nm) del canal que sigue este camino tras cruzar un material dispersivo; `None` = RGB.
+/// `travelled`: distancia recorrida desde la cámara (para el footprint de las texturas).
+fn trace_color(ctx: &Ctx, ray: Ray, depth: u32, absorption: Vec3, wavelength: Option<f32>, travelled: f32, rng: &mut Rng) -> Vec3 {
    if depth == 0 { return Vec3::new(0.0,0.0,0.0); }
 
-   if let Some(hit) = scene.trace(ray, 0.001, 1e9) {
-      let n = hit.n.normalize();
+   if let Some(hit) = ctx.scene.trace(ray, 0.001, 1e9) {
+      let n_geo = hit.n.normalize();
       let mat = hit.material();
-      let local = local_shade(scene, &hit, light_dir, sky, rng, env_samples);
+      let view = ray.dir.mul(-1.0);
+      // Tamaño del píxel proyectado sobre la superficie; cada forma dice cuánta UV cubre por unidad de mundo
+      let dist = travelled + hit.t;
+      let world = dist * ctx.pixel_angle / view.dot(n_geo).abs().max(0.1);
+      let footprint = Footprint { world, uv: world * hit.uv_density };
+
+      // Normal de shading (normal map) para iluminación, reflexión y refracción
+      let hit = Hit { n: mat.shading_normal(&hit, footprint), ..hit };
+      let n = hit.n;
+      let local = local_shade(ctx, &hit, view, footprint, rng);
+
+      // Normal orientada contra el rayo: hits desde dentro (vidrio, agua, esferas); se decide con la geométrica
+      let inside = ray.dir.dot(n_geo) > 0.0;
+      let n_face = if inside { n.mul(-1.0) } else { n };
 
       // Fresnel para mezcla
-      let view = ray.dir.mul(-1.0);
-      let f0 = mat.reflectivity.max(0.02);
-      let fresnel = crate::math::Vec3::fresnel_schlick(view.dot(n).max(0.0), f0);
+      let f0 = match mat.model {
+         ShadingModel::Legacy => mat.reflectivity.max(0.02),
+         ShadingModel::MetallicRoughness => 0.08 * mat.specular,
+      };
+      let fresnel = crate::math::Vec3::fresnel_schlick(view.dot(n_face).max(0.0), f0);
 
       let mut accum = local;
+      let reflect_ray = || Ray { origin: hit.p.add(n_face.mul(1e-3)), dir: crate::math::Vec3::reflect(ray.dir, n_face).normalize() };
 
-      if mat.reflectivity > 0.0 {
-         let refl_dir = crate::math::Vec3::reflect(ray.dir, n).normalize();
-         let refl_origin = hit.p.add(n.mul(1e-3));
-         let refl_col = trace_color(scene, Ray { origin: refl_origin, dir: refl_dir }, depth - 1, sky, env_samples, light_dir, rng);
-         accum = accum.add(refl_col.mul(mat.reflectivity * fresnel));
+      match mat.model {
+         ShadingModel::Legacy if mat.reflectivity > 0.0 => {
+            let refl_col = trace_color(ctx, reflect_ray(), depth - 1, absorption, wavelength, dist, rng);
+            accum = accum.add(refl_col.mul(mat.reflectivity * fresnel));
+         }
+         ShadingModel::MetallicRoughness => {
+            // Reflexión glossy: una dirección muestreada de GGX por muestra (converge al acumular)
+            let base = mat.albedo.hadamard(mat.texture.sample_with_normal(hit.uv, hit.p, n, footprint));
+            let surf = brdf::Surface::new(base, mat.metallic, mat.roughness, mat.specular);
+            if let Some((dir, weight)) = brdf::sample_specular(&surf, n_face, view, rng) {
+               let refl = Ray { origin: hit.p.add(n_face.mul(1e-3)), dir };
+               let refl_col = trace_color(ctx, refl, depth - 1, absorption, wavelength, dist, rng);
+               accum = accum.add(refl_col.hadamard(weight));
+            }
+         }
+         _ => {}
       }
 
       if mat.transparency > 0.0 {
-         let (n1, n2) = (1.0f32, mat.ior.max(1.0));
-         let mut n_out = n;
-         let mut eta = n1 / n2;
-         let mut cosi = (-ray.dir.dot(n)).clamp(-1.0, 1.0);
-         if cosi < 0.0 { cosi = -cosi; n_out = n.mul(-1.0); eta = n2 / n1; }
-         if let Some(refr_dir) = crate::math::Vec3::refract(ray.dir, n_out, eta) {
-               let refr_origin = hit.p.sub(n_out.mul(1e-3));
-               let refr_col = trace_color(scene, Ray { origin: refr_origin, dir: refr_dir.normalize() }, depth - 1, sky, env_samples, light_dir, rng);
-               let k_trans = mat.transparency * (1.0 - fresnel);
-               accum = accum.add(refr_col.mul(k_trans));
-         }
+         let k_trans = mat.transparency * (1.0 - fresnel);
+         let transmit = |wavelength: Option<f32>, rng: &mut Rng| {
+            let ior = mat.ior_at(wavelength).max(1.0);
+            let eta = if inside { ior } else { 1.0 / ior };
+            match crate::math::Vec3::refract(ray.dir, n_face, eta) {
+               Some(refr_dir) => {
+                  // Al entrar se pasa al medio del material; al salir, de vuelta al aire
+                  let refr_origin = hit.p.sub(n_face.mul(1e-3));
+                  let next = if inside { Vec3::new(0.0, 0.0, 0.0) } else { mat.absorption };
+                  trace_color(ctx, Ray { origin: refr_origin, dir: refr_dir.normalize() }, depth - 1, next, wavelength, dist, rng)
+               }
+               // Reflexión total interna: lo transmitido vuelve por reflexión (sigue en el mismo medio)
+               None => trace_color(ctx, reflect_ray(), depth - 1, absorption, wavelength, dist, rng),
+            }
+         };
+         let trans_col = match wavelength {
+            // Primer material dispersivo del camino: una λ por canal
+            None if mat.is_dispersive() => {
+               let r = transmit(Some(spectrum::sample_wavelength(0, rng)), rng);
+               let g = transmit(Some(spectrum::sample_wavelength(1, rng)), rng);
+               let b = transmit(Some(spectrum::sample_wavelength(2, rng)), rng);
+               Vec3::new(r.x, g.y, b.z)
+            }
+            _ => transmit(wavelength, rng),
+         };
+         accum = accum.add(trans_col.mul(k_trans));
       }
 
-      return accum.clamp01();
+      return accum.hadamard(transmittance(absorption, hit.t));
    }
 
-   miss_color(ray.dir, sky)
+   miss_color(ray.dir, ctx.sky).hadamard(transmittance(absorption, 1e9))
 }
 
-pub fn render_scene(image: &mut Image, scene: &Scene, cam: &OrbitCam, world_angle: f32, sky: Option<&Skybox>, env_samples: u32, frame_id: u64) {
+/// Renderiza en paralelo por tiles de TILE×TILE sobre todos los núcleos.
+/// La semilla del Rng depende solo de (frame_id, x, y): el resultado no depende del nº de hilos.
+pub fn render_scene(fb: &mut FrameBuffer, scene: &Scene, cam: &OrbitCam, world_angle: f32, sky: Option<&Skybox>, env_samples: u32, frame_id: u64) {
+   let (w, h) = (fb.width, fb.height);
    let eye = cam.eye();
    let (fwd, right, up) = cam.basis();
-   let aspect = (W as f32) / (H as f32);
+   let aspect = (w as f32) / (h as f32);
    let half_h = (cam.fov_deg.to_radians() * 0.5).tan();
    let half_w = aspect * half_h;
 
-   let light_dir = scene.light_dir.normalize().rot_y(world_angle);
+   let lights: Vec<Light> = scene.lights().iter().map(|l| l.rotated(world_angle)).collect();
+   let ctx = Ctx { scene, sky, env_samples, lights: &lights, pixel_angle: 2.0 * half_h / h as f32 };
    let max_depth = 4;
 
-   for y in 0..H {
-      for x in 0..W {
-         let u = ((x as f32 + 0.5) / (W as f32)) * 2.0 - 1.0;
-         let v = 1.0 - ((y as f32 + 0.5) / (H as f32)) * 2.0;
-
-         let dir = fwd.add(right.mul(u * half_w)).add(up.mul(v * half_h)).normalize();
-
-         let center = cam.target;
-         let origin_rel = eye.sub(center).rot_y(world_angle).add(center);
-         let dir_rot = dir.rot_y(world_angle);
-
-         let seed = (frame_id << 32) ^ ((y as u64) << 16) ^ (x as u64);
-         let mut rng = Rng::new(seed);
-
-         let color = trace_color(scene, Ray { origin: origin_rel, dir: dir_rot }, max_depth, sky, env_samples, light_dir, &mut rng).clamp01();
-
-         let col = Color::new((color.x*255.0) as u8, (color.y*255.0) as u8, (color.z*255.0) as u8, 255);
-         image.draw_pixel(x, y, col);
+   let center = cam.target;
+   let origin_rel = eye.sub(center).rot_y(world_angle).add(center);
+
+   let shade = |x: i32, y: i32| -> Vec3 {
+      let u = ((x as f32 + 0.5) / (w as f32)) * 2.0 - 1.0;
+      let v = 1.0 - ((y as f32 + 0.5) / (h as f32)) * 2.0;
+
+      let dir = fwd.add(right.mul(u * half_w)).add(up.mul(v * half_h)).normalize();
+      let dir_rot = dir.rot_y(world_angle);
+
+      let seed = (frame_id << 32) ^ ((y as u64) << 16) ^ (x as u64);
+      let mut rng = Rng::new(seed);
+
+      trace_color(&ctx, Ray { origin: origin_rel, dir: dir_rot }, max_depth, Vec3::new(0.0, 0.0, 0.0), None, 0.0, &mut rng)
+   };
+
+   // Reparto dinámico de tiles con un contador atómico
+   let tiles_x = (w + TILE - 1) / TILE;
+   let tiles_y = (h + TILE - 1) / TILE;
+   let n_tiles = (tiles_x * tiles_y) as usize;
+   let next = AtomicUsize::new(0);
+   let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(n_tiles.max(1));
+
+   let tiles: Vec<(usize, Vec<Vec3>)> = thread::scope(|s| {
+      let workers: Vec<_> = (0..threads).map(|_| s.spawn(|| {
+         let mut done = Vec::new();
+         loop {
+            let t = next.fetch_add(1, Ordering::Relaxed);
+            if t >= n_tiles { break; }
+            let (x0, y0) = ((t as i32 % tiles_x) * TILE, (t as i32 / tiles_x) * TILE);
+            let mut px = Vec::with_capacity((TILE * TILE) as usize);
+            for y in y0..(y0 + TILE).min(h) {
+               for x in x0..(x0 + TILE).min(w) { px.push(shade(x, y)); }
+            }
+            done.push((t, px));
+         }
+         done
+      })).collect();
+      workers.into_iter().flat_map(|wk| wk.join().expect("hilo de render")).collect()
+   });
+
+   // Copio cada tile a su lugar en el framebuffer
+   for (t, px) in tiles {
+      let (x0, y0) = ((t as i32 % tiles_x) * TILE, (t as i32 / tiles_x) * TILE);
+      let tw = ((x0 + TILE).min(w) - x0) as usize;
+      for (row, chunk) in px.chunks(tw).enumerate() {
+         let start = ((y0 as usize + row) * w as usize) + x0 as usize;
+         fb.pixels[start..start + tw].copy_from_slice(chunk);
       }
    }
 }
@@ -1,20 +1,82 @@
-use crate::{math::Vec3, ray::Ray, material::Hit};
+use std::sync::OnceLock;
+use crate::{ray::Ray, material::Hit, light::{Light, AreaLight}};
 use crate::shapes::Hittable;
+use crate::bvh::Bvh;
+
+// Tope de superficies recortadas que un rayo puede atravesar
+const MAX_CUTOUT_SKIPS: usize = 32;
+
+// Estructura de aceleración: objetos acotados en el BVH, infinitos (planos) aparte
+struct Accel {
+   bvh: Bvh,
+   unbounded: Vec<usize>,
+   emitters: Vec<AreaLight>,
+}
 
 pub struct Scene {
-   pub objects: Vec<Box<dyn Hittable>>,
-   pub light_dir: Vec3,
+   objects: Vec<Box<dyn Hittable>>,
+   lights: Vec<Light>,
+   accel: OnceLock<Accel>,
+   revision: u64,
 }
 impl Scene {
-   pub fn new(light_dir: Vec3) -> Self { Self { objects: Vec::new(), light_dir } }
-   pub fn add(&mut self, o: Box<dyn Hittable>) { self.objects.push(o); }
-   pub fn trace(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit> {
-      let mut hit: Option<Hit> = None;
-      let mut closest = tmax;
-      for o in &self.objects {
-         if let Some(h) = o.hit(ray, tmin, closest) {
-               closest = h.t;
-               hit = Some(h);
+   pub fn new() -> Self { Self { objects: Vec::new(), lights: Vec::new(), accel: OnceLock::new(), revision: 0 } }
+   pub fn add(&mut self, o: Box<dyn Hittable>) {
+      self.objects.push(o);
+      self.accel = OnceLock::new(); // se reconstruye en el próximo trace
+      self.revision += 1;
+   }
+
+   pub fn add_light(&mut self, l: Light) {
+      self.lights.push(l);
+      self.revision += 1;
+   }
+
+   pub fn objects(&self) -> &[Box<dyn Hittable>] { &self.objects }
+   pub fn lights(&self) -> &[Light] { &self.lights }
+
+   /// Cambia cada vez que se modifica la escena (invalida la acumulación progresiva).
+   pub fn revision(&self) -> u64 { self.revision }
+
+   fn accel(&self) -> &Accel {
+      self.accel.get_or_init(|| {
+         let mut items = Vec::new();
+         let mut unbounded = Vec::new();
+         let mut emitters = Vec::new();
+         for (i, o) in self.objects.iter().enumerate() {
+            emitters.extend(o.emitters());
+            match o.bounding_box() {
+               Some(bb) => items.push((i, bb)),
+               None => unbounded.push(i),
+            }
+         }
+         Accel { bvh: Bvh::build(items), unbounded, emitters }
+      })
+   }
+
+   /// Objetos emisivos de la escena, muestreados como luces de área.
+   pub fn emitters(&self) -> &[AreaLight] { &self.accel().emitters }
+
+   /// Impacto más cercano, saltando los texels recortados por alfa (vale igual para
+   /// rayos primarios, de sombra y secundarios).
+   pub fn trace(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      let mut t0 = tmin;
+      for _ in 0..MAX_CUTOUT_SKIPS {
+         let hit = self.closest_hit(ray, t0, tmax)?;
+         if !hit.material().is_cutout(hit.uv, hit.n) { return Some(hit); }
+         t0 = hit.t + 1e-4;
+      }
+      None
+   }
+
+   fn closest_hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      let accel = self.accel();
+      let mut hit = accel.bvh.trace(ray, tmin, tmax, |i, closest| self.objects[i].hit(ray, tmin, closest));
+      let mut closest = hit.map_or(tmax, |h| h.t);
+      for &i in &accel.unbounded {
+         if let Some(h) = self.objects[i].hit(ray, tmin, closest) {
+            closest = h.t;
+            hit = Some(h);
          }
       }
       hit
@@ -0,0 +1,495 @@
+// ==========================================================
+// Formato de escena en texto (una directiva por línea)
+// ==========================================================
+//
+//   # comentario
+//   sky assets/sky.jpg [intensity=i] [rotation=grados]   (o `sky none` para el gradiente)
+//                                                     .hdr (Radiance RGBE) da radiancia HDR real para el IBL
+//   sun -0.25 -1.0 -0.35 [color=r,g,b] [intensity=i]   luz direccional principal (o `sun none`)
+//   light point <x,y,z> [color=r,g,b] [intensity=i]    caída 1/d²
+//   light spot <x,y,z> <dx,dy,dz> [inner=grados] [outer=grados] [color=r,g,b] [intensity=i]
+//   light directional <dx,dy,dz> [color=r,g,b] [intensity=i]
+//   camera target=0,4,0 yaw=0.9 pitch=-0.5 dist=9.2 fov=60
+//
+//   texture <nombre> image <ruta> [fallback=<textura>] [filter=nearest|bilinear|trilinear]
+//   texture <nombre> checker <escala> <r,g,b> <r,g,b>
+//   texture <nombre> atlas <top> <side> <bottom>      (nombres de texturas ya definidas)
+//   texture <nombre> region <imagen> rect=x,y,w,h      sub-rectángulo (píxeles, origen arriba a la izquierda;
+//                                                     si <imagen> ya es una región, relativo a ella)
+//   texture <nombre> region <imagen> tile=<i> grid=<cols>x<filas>   baldosa i (por filas) de un atlas en grilla
+//   sheet <ruta> grid=<cols>x<filas> names=a,b,_,c [filter=..]   corta un terrain sheet y define una textura
+//                                                     por baldosa con nombre (`_` salta una)
+//   texture <nombre> faces <+x> <-x> <+y> <-y> <+z> <-z>  atlas de seis caras (este, oeste, arriba, abajo, sur, norte)
+//   texture <nombre> bump <textura> [strength=s]       normal map a partir del relieve (luminancia) de otra textura
+//   texture <nombre> noise perlin|simplex|fbm|turbulence|marble|wood|voronoi
+//           [space=world|uv] [scale=s] [octaves=n] [distortion=d] [ramp=t:r,g,b/t:r,g,b/...]
+//                                                     ruido procedural; la rampa por defecto va de negro a blanco
+//
+//   material <nombre> albedo=r,g,b kd=.. specular=.. transparency=.. reflectivity=.. ior=.. texture=<textura>
+//            [metallic=.. roughness=..]                 cualquiera de los dos activa el modelo GGX
+//                                                       (ahí specular= es la reflectancia, default 0.5)
+//            [model=legacy|pbr]
+//            [emission=r,g,b emission_strength=..]      superficie emisiva (también luz de área)
+//            [abbe=..]                                  dispersión (número de Abbe; menor = más fuego)
+//            [absorption=r,g,b]                         Beer–Lambert por unidad de distancia (agua, vidrio teñido)
+//            [normal_map=<textura> normal_strength=..]  normales en espacio tangente
+//            [alpha_cutoff=..]                          texels con alfa menor se atraviesan (hojas)
+//
+//   block <x> <y> <z> <bloque>                        bloque unitario en la grilla (coordenadas en ±2^20)
+//   fill <x0> <y0> <z0> <x1> <y1> <z1> <bloque>       caja de bloques (inclusiva)
+//   box <x,y,z> <x,y,z> <bloque>                      Cube arbitrario (min, max)
+//     <bloque> = <material> [facing=north|south|east|west|up|down | axis=x|y|z]
+//              o seis materiales por cara `+x,-x,+y,-y,+z,-z` (p.ej. `side,side,top,bottom,side,front`)
+//     facing: hacia dónde mira la cara norte (-Z) del bloque; axis: eje del Y local (troncos)
+//   sphere <x,y,z> <radio> <material>                 esfera
+//   mesh <ruta.obj> [scale=s] [translate=x,y,z] [material=<material>]
+//                                                     malla OBJ (+MTL); `material` reemplaza los del MTL
+//   plane <y> <material>                              plano horizontal infinito
+//
+// Las rutas son relativas al directorio de trabajo (igual que `assets/...` en main.rs).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::{math::Vec3, scene::Scene, camera::OrbitCam, light::Light, material::{Material, ShadingModel, DIELECTRIC_SPECULAR}, texture::{Texture, Filter, Rect}, skybox::Skybox, noise::{self, Noise}};
+use crate::shapes::{Hittable, Cube, Plane, Sphere, VoxelGrid, voxel::{self, BlockId}, cube::{Block, Facing, Orientation}};
+use crate::obj;
+
+pub struct SceneDesc {
+   pub scene: Scene,
+   pub cam: OrbitCam,
+   pub sky: Option<Skybox>,
+}
+
+pub fn load(path: &str) -> Result<SceneDesc, String> {
+   let src = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+   parse(&src).map_err(|(line, msg)| format!("{path}:{line}: {msg}"))
+}
+
+struct Builder {
+   scene: Scene,
+   cam: OrbitCam,
+   sky: Option<Skybox>,
+   sun: Option<Light>,
+   world: VoxelGrid,
+   textures: HashMap<String, Texture>,
+   materials: HashMap<String, Material>,
+   block_ids: HashMap<String, BlockId>,
+}
+
+fn parse(src: &str) -> Result<SceneDesc, (usize, String)> {
+   let mut b = Builder {
+      scene: Scene::new(),
+      cam: OrbitCam { target: Vec3::new(0.0, 0.0, 0.0), yaw: 0.9, pitch: -0.5, dist: 9.0, fov_deg: 60.0 },
+      sky: None,
+      sun: Some(Light::sun(Vec3::new(-0.25, -1.0, -0.35))),
+      world: VoxelGrid::new(),
+      textures: HashMap::new(),
+      materials: HashMap::new(),
+      block_ids: HashMap::new(),
+   };
+
+   for (i, raw) in src.lines().enumerate() {
+      let line = raw.split('#').next().unwrap_or("").trim();
+      if line.is_empty() { continue; }
+      let tokens: Vec<&str> = line.split_whitespace().collect();
+      b.directive(&tokens).map_err(|e| (i + 1, e))?;
+   }
+
+   let Builder { mut scene, cam, sky, sun, world, .. } = b;
+   if let Some(sun) = sun { scene.add_light(sun); }
+   if world.bounding_box().is_some() { scene.add(Box::new(world)); }
+   Ok(SceneDesc { scene, cam, sky })
+}
+
+impl Builder {
+   fn directive(&mut self, t: &[&str]) -> Result<(), String> {
+      match t[0] {
+         "sky" => {
+            let path = arg(t, 1)?;
+            if path == "none" { self.sky = None; return Ok(()); }
+            let (mut intensity, mut rotation) = (1.0, 0.0f32);
+            for (k, v) in pairs(&t[2..])? {
+               match k {
+                  "intensity" => intensity = num(v)?,
+                  "rotation" => rotation = num(v)?,
+                  _ => return Err(format!("parámetro de sky desconocido: {k}")),
+               }
+            }
+            self.sky = match Skybox::load(path) {
+               Ok(sky) => Some(Skybox { intensity, rotation: rotation.to_radians(), ..sky }),
+               Err(e) => { eprintln!("(info) {e} — usando gradiente."); None }
+            };
+         }
+         "sun" => {
+            if arg(t, 1)? == "none" { self.sun = None; return Ok(()); }
+            let dir = Vec3::new(num(arg(t, 1)?)?, num(arg(t, 2)?)?, num(arg(t, 3)?)?);
+            let (color, intensity) = light_params(&t[4..])?;
+            self.sun = Some(Light::Directional { dir, color, intensity });
+         }
+         "light" => {
+            let light = match arg(t, 1)? {
+               "point" => {
+                  let pos = vec3(arg(t, 2)?)?;
+                  let (color, intensity) = light_params(&t[3..])?;
+                  Light::Point { pos, color, intensity }
+               }
+               "spot" => {
+                  let (pos, dir) = (vec3(arg(t, 2)?)?, vec3(arg(t, 3)?)?);
+                  let (mut inner, mut outer) = (20.0f32, 30.0f32);
+                  let mut rest = Vec::new();
+                  for (k, v) in pairs(&t[4..])? {
+                     match k {
+                        "inner" => inner = num(v)?,
+                        "outer" => outer = num(v)?,
+                        _ => rest.push((k, v)),
+                     }
+                  }
+                  let (color, intensity) = light_kv(&rest)?;
+                  Light::Spot { pos, dir, color, intensity, inner: inner.to_radians(), outer: outer.to_radians() }
+               }
+               "directional" => {
+                  let dir = vec3(arg(t, 2)?)?;
+                  let (color, intensity) = light_params(&t[3..])?;
+                  Light::Directional { dir, color, intensity }
+               }
+               other => return Err(format!("tipo de luz desconocido: {other}")),
+            };
+            self.scene.add_light(light);
+         }
+         "camera" => {
+            for (k, v) in pairs(&t[1..])? {
+               match k {
+                  "target" => self.cam.target = vec3(v)?,
+                  "yaw" => self.cam.yaw = num(v)?,
+                  "pitch" => self.cam.pitch = num(v)?,
+                  "dist" => self.cam.dist = num(v)?,
+                  "fov" => self.cam.fov_deg = num(v)?,
+                  _ => return Err(format!("parámetro de cámara desconocido: {k}")),
+               }
+            }
+         }
+         "texture" => {
+            let name = arg(t, 1)?.to_string();
+            let tex = self.texture(t)?;
+            self.textures.insert(name, tex);
+         }
+         "sheet" => {
+            let path = arg(t, 1)?;
+            let (mut dims, mut names, mut filter) = (None, Vec::new(), Filter::Nearest);
+            for (k, v) in pairs(&t[2..])? {
+               match k {
+                  "grid" => dims = Some(grid(v)?),
+                  "names" => names = v.split(',').collect(),
+                  "filter" => filter = parse_filter(v)?,
+                  _ => return Err(format!("parámetro de sheet desconocido: {k}")),
+               }
+            }
+            let (cols, rows) = dims.ok_or("sheet: falta grid=<cols>x<filas>")?;
+            // Sin imagen no se define ninguna baldosa: las texturas que las usen deben tener respaldo
+            match Texture::load_sheet(path, cols, rows, &names)? {
+               Some(tiles) => for (n, tex) in tiles { self.textures.insert(n, tex.with_filter(filter)); },
+               None => eprintln!("(info) No se encontró {path} — baldosas sin definir."),
+            }
+         }
+         "material" => {
+            let name = arg(t, 1)?.to_string();
+            let (mut m, mut specular) = (Material::default(), None);
+            for (k, v) in pairs(&t[2..])? {
+               match k {
+                  "albedo" => m.albedo = vec3(v)?,
+                  "kd" => m.kd = num(v)?,
+                  "specular" => specular = Some(num(v)?),
+                  "transparency" => m.transparency = num(v)?,
+                  "reflectivity" => m.reflectivity = num(v)?,
+                  "ior" => m.ior = num(v)?,
+                  "texture" => m.texture = self.texture_ref(v)?,
+                  "metallic" => { m.metallic = num(v)?; m.model = ShadingModel::MetallicRoughness; }
+                  "roughness" => { m.roughness = num(v)?; m.model = ShadingModel::MetallicRoughness; }
+                  "abbe" => m.abbe = num(v)?,
+                  "absorption" => m.absorption = vec3(v)?,
+                  "normal_map" => m.normal_map = self.texture_ref(v)?.as_data(),
+                  "normal_strength" => m.normal_strength = num(v)?,
+                  "alpha_cutoff" => m.alpha_cutoff = num(v)?,
+                  "emission" => m.emission = vec3(v)?,
+                  "emission_strength" => m.emission_strength = num(v)?,
+                  "model" => m.model = match v {
+                     "legacy" => ShadingModel::Legacy,
+                     "pbr" => ShadingModel::MetallicRoughness,
+                     _ => return Err(format!("modelo de shading desconocido: {v}")),
+                  },
+                  _ => return Err(format!("parámetro de material desconocido: {k}")),
+               }
+            }
+            // Sin `specular=`, cada modelo usa su default (Phong 0.2 / reflectancia 0.5)
+            m.specular = specular.unwrap_or(if m.model == ShadingModel::MetallicRoughness { DIELECTRIC_SPECULAR } else { m.specular });
+            self.materials.insert(name, m);
+         }
+         "block" => {
+            let id = self.block_id(arg(t, 4)?, &t[5..])?;
+            self.world.try_set(int(arg(t, 1)?)?, int(arg(t, 2)?)?, int(arg(t, 3)?)?, id)?;
+         }
+         "fill" => {
+            let id = self.block_id(arg(t, 7)?, &t[8..])?;
+            let (x0, y0, z0) = (int(arg(t, 1)?)?, int(arg(t, 2)?)?, int(arg(t, 3)?)?);
+            let (x1, y1, z1) = (int(arg(t, 4)?)?, int(arg(t, 5)?)?, int(arg(t, 6)?)?);
+            // Se valida la caja entera antes de tocar la grilla (un fill enorme no llega a reservar nada)
+            let range = |a: i32, b: i32| (a.min(b), a.max(b));
+            let ranges = [range(x0, x1), range(y0, y1), range(z0, z1)];
+            if ranges.iter().any(|&(lo, hi)| lo < -voxel::MAX_COORD || hi >= voxel::MAX_COORD) {
+               return Err(format!("fill fuera de la grilla (coordenadas en ±{})", voxel::MAX_COORD));
+            }
+            let volume = ranges.iter().map(|&(lo, hi)| (hi - lo + 1) as u64).product::<u64>();
+            if volume > voxel::MAX_CELLS as u64 { return Err(format!("fill de {volume} bloques (máximo {})", voxel::MAX_CELLS)); }
+            let [(xa, xb), (ya, yb), (za, zb)] = ranges;
+            for z in za..=zb {
+               for y in ya..=yb {
+                  for x in xa..=xb { self.world.try_set(x, y, z, id)?; }
+               }
+            }
+         }
+         "box" => {
+            let (min, max) = (vec3(arg(t, 1)?)?, vec3(arg(t, 2)?)?);
+            let block = self.block(arg(t, 3)?, &t[4..])?;
+            self.scene.add(Box::new(Cube { min: min.min(max), max: min.max(max), block }));
+         }
+         "sphere" => {
+            let (center, radius) = (vec3(arg(t, 1)?)?, num(arg(t, 2)?)?);
+            if radius <= 0.0 { return Err(format!("radio inválido: {radius}")); }
+            let mat = self.material(arg(t, 3)?)?;
+            self.scene.add(Box::new(Sphere { center, radius, mat }));
+         }
+         "mesh" => {
+            let mut mesh = obj::load_obj(arg(t, 1)?)?;
+            let (mut scale, mut offset) = (1.0, Vec3::new(0.0, 0.0, 0.0));
+            for (k, v) in pairs(&t[2..])? {
+               match k {
+                  "scale" => scale = num(v)?,
+                  "translate" => offset = vec3(v)?,
+                  "material" => { let m = self.material(v)?; mesh.materials.iter_mut().for_each(|mm| *mm = m.clone()); }
+                  _ => return Err(format!("parámetro de malla desconocido: {k}")),
+               }
+            }
+            mesh.transform(scale, offset);
+            self.scene.add(Box::new(mesh));
+         }
+         "plane" => {
+            let y = num(arg(t, 1)?)?;
+            let mat = self.material(arg(t, 2)?)?;
+            self.scene.add(Box::new(Plane { y, mat }));
+         }
+         other => return Err(format!("directiva desconocida: {other}")),
+      }
+      Ok(())
+   }
+
+   fn texture(&self, t: &[&str]) -> Result<Texture, String> {
+      match arg(t, 2)? {
+         "image" => {
+            let path = arg(t, 3)?;
+            let (mut fallback, mut filter) = (None, Filter::Nearest);
+            for (k, v) in pairs(&t[4..])? {
+               match k {
+                  "fallback" => fallback = Some(self.texture_ref(v)?),
+                  "filter" => filter = parse_filter(v)?,
+                  _ => return Err(format!("parámetro de imagen desconocido: {k}")),
+               }
+            }
+            match (Texture::from_file(path), fallback) {
+               (Some(tex), _) => Ok(tex.with_filter(filter)),
+               (None, Some(fb)) => Ok(fb),
+               (None, None) => Err(format!("no se pudo cargar {path}")),
+            }
+         }
+         "checker" => Ok(Texture::Checker { scale: num(arg(t, 3)?)?, a: vec3(arg(t, 4)?)?, b: vec3(arg(t, 5)?)? }),
+         "atlas" => Ok(Texture::BlockAtlas {
+            top: Arc::new(self.atlas_entry(arg(t, 3)?)?),
+            side: Arc::new(self.atlas_entry(arg(t, 4)?)?),
+            bottom: Arc::new(self.atlas_entry(arg(t, 5)?)?),
+         }),
+         "region" => {
+            let src = self.texture_ref(arg(t, 3)?)?;
+            let (mut rect, mut index, mut dims) = (None, None, None);
+            for (k, v) in pairs(&t[4..])? {
+               match k {
+                  "rect" => {
+                     let c: Vec<&str> = v.split(',').collect();
+                     if c.len() != 4 { return Err(format!("se esperaba x,y,w,h: {v}")); }
+                     rect = Some(Rect { x: int(c[0])?, y: int(c[1])?, w: int(c[2])?, h: int(c[3])? });
+                  }
+                  "tile" => index = Some(int(v)?),
+                  "grid" => dims = Some(grid(v)?),
+                  _ => return Err(format!("parámetro de región desconocido: {k}")),
+               }
+            }
+            if matches!(src, Texture::Image { .. } | Texture::AtlasRegion { .. }) {
+               let region = match (rect, index, dims) {
+                  (Some(r), None, None) => src.region(r),
+                  (None, Some(i), Some((c, r))) => src.tile(c, r, i),
+                  _ => return Err("region: usar rect=x,y,w,h o tile=<i> grid=<cols>x<filas>".to_string()),
+               };
+               region.ok_or_else(|| "region fuera de la imagen".to_string())
+            } else {
+               // La imagen no cargó (damero de respaldo): se usa entera
+               Ok(src)
+            }
+         }
+         "faces" => {
+            let mut faces = Vec::with_capacity(6);
+            for i in 3..9 { faces.push(Arc::new(self.atlas_entry(arg(t, i)?)?)); }
+            Ok(Texture::FaceAtlas(faces.try_into().map_err(|_| "se esperaban seis texturas".to_string())?))
+         }
+         "bump" => {
+            let src = self.texture_ref(arg(t, 3)?)?;
+            let strength = match pairs(&t[4..])?.as_slice() {
+               [] => 4.0,
+               [("strength", v)] => num(v)?,
+               _ => return Err("se esperaba solo strength=<s>".to_string()),
+            };
+            // Sin imagen (damero de respaldo) no hay relieve: normal map plano
+            Ok(src.normal_map_from_height(strength).unwrap_or(Texture::None))
+         }
+         "noise" => {
+            let pattern = match arg(t, 3)? {
+               "perlin" => noise::Pattern::Perlin,
+               "simplex" => noise::Pattern::Simplex,
+               "fbm" => noise::Pattern::Fbm,
+               "turbulence" => noise::Pattern::Turbulence,
+               "marble" => noise::Pattern::Marble,
+               "wood" => noise::Pattern::Wood,
+               "voronoi" => noise::Pattern::Voronoi,
+               other => return Err(format!("patrón de ruido desconocido: {other}")),
+            };
+            let mut n = Noise::new(pattern);
+            for (k, v) in pairs(&t[4..])? {
+               match k {
+                  "space" => n.space = match v {
+                     "world" => noise::Space::World,
+                     "uv" => noise::Space::Uv,
+                     _ => return Err(format!("espacio de ruido desconocido: {v}")),
+                  },
+                  "scale" => n.scale = num(v)?,
+                  "octaves" => n.octaves = int(v)?.max(1) as u32,
+                  "distortion" => n.distortion = num(v)?,
+                  "ramp" => n.ramp = ramp(v)?,
+                  _ => return Err(format!("parámetro de ruido desconocido: {k}")),
+               }
+            }
+            Ok(Texture::Noise(Arc::new(n)))
+         }
+         other => Err(format!("tipo de textura desconocido: {other}")),
+      }
+   }
+
+   fn texture_ref(&self, name: &str) -> Result<Texture, String> {
+      self.textures.get(name).cloned().ok_or_else(|| format!("textura no definida: {name}"))
+   }
+
+   // Entrada de un atlas: cualquier textura menos otro atlas
+   fn atlas_entry(&self, name: &str) -> Result<Texture, String> {
+      match self.texture_ref(name)? {
+         Texture::BlockAtlas { .. } | Texture::FaceAtlas(_) => Err(format!("{name} es un atlas y no puede ir dentro de otro atlas")),
+         tex => Ok(tex),
+      }
+   }
+
+   fn material(&self, name: &str) -> Result<Material, String> {
+      self.materials.get(name).cloned().ok_or_else(|| format!("material no definido: {name}"))
+   }
+
+   // <material> o `+x,-x,+y,-y,+z,-z`, más facing= / axis= opcionales
+   fn block(&self, spec: &str, opts: &[&str]) -> Result<Block, String> {
+      let names: Vec<&str> = spec.split(',').collect();
+      let block = match names.as_slice() {
+         [name] => Block::new(self.material(name)?),
+         [..] if names.len() == 6 => {
+            let mut faces = Vec::with_capacity(6);
+            for name in &names { faces.push(self.material(name)?); }
+            Block::with_faces(faces.try_into().map_err(|_| "se esperaban seis materiales".to_string())?)
+         }
+         _ => return Err(format!("se esperaba un material o seis (uno por cara): {spec}")),
+      };
+      let mut orient = Orientation::IDENTITY;
+      for (k, v) in pairs(opts)? {
+         orient = match (k, v) {
+            ("facing", "north") => Orientation::facing(Facing::North),
+            ("facing", "south") => Orientation::facing(Facing::South),
+            ("facing", "east") => Orientation::facing(Facing::East),
+            ("facing", "west") => Orientation::facing(Facing::West),
+            ("facing", "up") => Orientation::facing(Facing::Up),
+            ("facing", "down") => Orientation::facing(Facing::Down),
+            ("axis", "x") => Orientation::axis(0),
+            ("axis", "y") => Orientation::axis(1),
+            ("axis", "z") => Orientation::axis(2),
+            _ => return Err(format!("orientación inválida: {k}={v}")),
+         };
+      }
+      Ok(block.oriented(orient))
+   }
+
+   // Un id de bloque por combinación material(es) + orientación, registrado la primera vez que se usa
+   fn block_id(&mut self, spec: &str, opts: &[&str]) -> Result<BlockId, String> {
+      let key = std::iter::once(spec).chain(opts.iter().copied()).collect::<Vec<_>>().join(" ");
+      if let Some(id) = self.block_ids.get(&key) { return Ok(*id); }
+      let id = self.world.try_add_block(self.block(spec, opts)?)?;
+      self.block_ids.insert(key, id);
+      Ok(id)
+   }
+}
+
+// --- Helpers de parseo ---
+fn arg<'a>(t: &[&'a str], i: usize) -> Result<&'a str, String> {
+   t.get(i).copied().ok_or_else(|| format!("faltan argumentos para `{}`", t[0]))
+}
+
+fn parse_filter(v: &str) -> Result<Filter, String> {
+   match v {
+      "nearest" => Ok(Filter::Nearest),
+      "bilinear" => Ok(Filter::Bilinear),
+      "trilinear" => Ok(Filter::Trilinear),
+      _ => Err(format!("filtro desconocido: {v}")),
+   }
+}
+
+// "16x16" → (columnas, filas)
+fn grid(v: &str) -> Result<(i32, i32), String> {
+   let (c, r) = v.split_once('x').ok_or_else(|| format!("se esperaba <cols>x<filas>: {v}"))?;
+   Ok((int(c)?, int(r)?))
+}
+
+fn num(s: &str) -> Result<f32, String> { s.parse().map_err(|_| format!("número inválido: {s}")) }
+fn int(s: &str) -> Result<i32, String> { s.parse().map_err(|_| format!("entero inválido: {s}")) }
+
+fn vec3(s: &str) -> Result<Vec3, String> {
+   let c: Vec<&str> = s.split(',').collect();
+   if c.len() != 3 { return Err(format!("se esperaba x,y,z: {s}")); }
+   Ok(Vec3::new(num(c[0])?, num(c[1])?, num(c[2])?))
+}
+
+// color=r,g,b intensity=i (por defecto blanco, 1)
+fn light_params(t: &[&str]) -> Result<(Vec3, f32), String> { light_kv(&pairs(t)?) }
+
+fn light_kv(kv: &[(&str, &str)]) -> Result<(Vec3, f32), String> {
+   let (mut color, mut intensity) = (Vec3::new(1.0, 1.0, 1.0), 1.0);
+   for &(k, v) in kv {
+      match k {
+         "color" => color = vec3(v)?,
+         "intensity" => intensity = num(v)?,
+         _ => return Err(format!("parámetro de luz desconocido: {k}")),
+      }
+   }
+   Ok((color, intensity))
+}
+
+// "t:r,g,b/t:r,g,b/..." → paradas de la rampa ordenadas por t
+fn ramp(s: &str) -> Result<Vec<(f32, Vec3)>, String> {
+   let mut stops = s.split('/').map(|stop| {
+      let (t, c) = stop.split_once(':').ok_or_else(|| format!("parada de rampa inválida: {stop}"))?;
+      Ok((num(t)?, vec3(c)?))
+   }).collect::<Result<Vec<_>, String>>()?;
+   stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+   Ok(stops)
+}
+
+fn pairs<'a>(t: &[&'a str]) -> Result<Vec<(&'a str, &'a str)>, String> {
+   t.iter().map(|kv| kv.split_once('=').ok_or_else(|| format!("se esperaba clave=valor: {kv}"))).collect()
+}
@@ -1,10 +1,112 @@
-use crate::{math::Vec3, ray::Ray, material::{Material, Hit}};
+use crate::{math::Vec3, ray::Ray, material::{Material, Hit}, bvh::Aabb, light::{AreaLight, AreaShape}, texture::face_index};
 use super::Hittable;
 
-pub struct Cube { pub min: Vec3, pub max: Vec3, pub mat: Material }
+/// Hacia dónde mira la cara frontal (-Z local, "norte") de un bloque.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Facing { North, South, East, West, Up, Down }
+
+/// Rotación de un bloque: ejes locales expresados en mundo (permutación con signo).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orientation { x: Vec3, y: Vec3, z: Vec3 }
+
+impl Orientation {
+   pub const IDENTITY: Self = Self { x: Vec3 { x: 1.0, y: 0.0, z: 0.0 }, y: Vec3 { x: 0.0, y: 1.0, z: 0.0 }, z: Vec3 { x: 0.0, y: 0.0, z: 1.0 } };
+
+   /// Frente hacia `f`; las horizontales giran alrededor de Y (el techo sigue arriba).
+   pub fn facing(f: Facing) -> Self {
+      let v = Vec3::new;
+      let (x, y, z) = match f {
+         Facing::North => return Self::IDENTITY,
+         Facing::South => (v(-1.0, 0.0, 0.0), v(0.0, 1.0, 0.0), v(0.0, 0.0, -1.0)),
+         Facing::East => (v(0.0, 0.0, 1.0), v(0.0, 1.0, 0.0), v(-1.0, 0.0, 0.0)),
+         Facing::West => (v(0.0, 0.0, -1.0), v(0.0, 1.0, 0.0), v(1.0, 0.0, 0.0)),
+         Facing::Up => (v(1.0, 0.0, 0.0), v(0.0, 0.0, 1.0), v(0.0, -1.0, 0.0)),
+         Facing::Down => (v(1.0, 0.0, 0.0), v(0.0, 0.0, -1.0), v(0.0, 1.0, 0.0)),
+      };
+      Self { x, y, z }
+   }
+
+   /// Eje Y local (p.ej. los anillos de un tronco) alineado con el eje de mundo `axis` (0 = X, 1 = Y, 2 = Z).
+   pub fn axis(axis: usize) -> Self {
+      let v = Vec3::new;
+      match axis {
+         0 => Self { x: v(0.0, -1.0, 0.0), y: v(1.0, 0.0, 0.0), z: v(0.0, 0.0, 1.0) },
+         2 => Self { x: v(1.0, 0.0, 0.0), y: v(0.0, 0.0, 1.0), z: v(0.0, -1.0, 0.0) },
+         _ => Self::IDENTITY,
+      }
+   }
+
+   fn to_local(self, v: Vec3) -> Vec3 { Vec3::new(v.dot(self.x), v.dot(self.y), v.dot(self.z)) }
+   fn to_world(self, v: Vec3) -> Vec3 { self.x.mul(v.x).add(self.y.mul(v.y)).add(self.z.mul(v.z)) }
+}
+
+impl Default for Orientation {
+   fn default() -> Self { Self::IDENTITY }
+}
+
+/// Materiales por cara (+X, -X, +Y, -Y, +Z, -Z en el marco local) y orientación.
+/// Lo comparten `Cube` y las entradas de la paleta de `VoxelGrid`.
+#[derive(Debug, Clone)]
+pub struct Block {
+   pub faces: [Material; 6],
+   pub orient: Orientation,
+}
+
+impl Block {
+   /// Mismo material en las seis caras; si su textura es un atlas se resuelve por cara.
+   pub fn new(mat: Material) -> Self {
+      Self::with_faces(std::array::from_fn(|_| mat.clone()))
+   }
+
+   /// Un material por cara (cada uno con su atlas, si lo tiene, resuelto a esa cara).
+   pub fn with_faces(faces: [Material; 6]) -> Self {
+      let faces = std::array::from_fn(|i| {
+         let m = &faces[i];
+         Material { texture: m.texture.for_face(i), normal_map: m.normal_map.for_face(i), ..m.clone() }
+      });
+      Self { faces, orient: Orientation::IDENTITY }
+   }
+
+   pub fn oriented(self, orient: Orientation) -> Self { Self { orient, ..self } }
+
+   /// Impacto en la caja [min,max] con normal de mundo `n`: UV y tangentes se calculan
+   /// en el marco local (rotado alrededor del centro) y el material es el de esa cara.
+   pub fn hit_at(&self, t: f32, p: Vec3, n: Vec3, min: Vec3, max: Vec3) -> Hit<'_> {
+      let o = self.orient;
+      let c = min.add(max).mul(0.5);
+      let h = o.to_local(max.sub(c));
+      let h = Vec3::new(h.x.abs(), h.y.abs(), h.z.abs());
+      let (pl, nl) = (c.add(o.to_local(p.sub(c))), o.to_local(n));
+      let (tangent, bitangent) = face_tangents(nl);
+      // La cara entera es una vuelta de UV: densidad según su área
+      let s = h.mul(2.0);
+      let area = if nl.x.abs() > 0.5 { s.y * s.z } else if nl.y.abs() > 0.5 { s.x * s.z } else { s.x * s.y };
+      Hit {
+         t, p, n,
+         mat: &self.faces[face_index(nl)],
+         uv: face_uv(nl, pl, c.sub(h), c.add(h)),
+         tangent: o.to_world(tangent),
+         bitangent: o.to_world(bitangent),
+         uv_density: 1.0 / area.sqrt().max(1e-6),
+      }
+   }
+
+   pub fn is_emissive(&self) -> bool { self.faces.iter().any(Material::is_emissive) }
+
+   /// ¿Las celdas vecinas de este bloque forman un solo medio? Solo los transparentes sin recorte
+   /// (agua, vidrio); en los recortados (hojas) y opacos cada borde de celda es una cara.
+   pub fn is_medium(&self) -> bool { self.faces.iter().all(|m| m.transparency > 0.0 && m.alpha_cutoff <= 0.0) }
+
+   /// Radiancia media de las caras (la luz de área emite igual desde toda la caja).
+   pub fn emitted(&self) -> Vec3 {
+      self.faces.iter().fold(Vec3::new(0.0, 0.0, 0.0), |acc, m| acc.add(m.emitted())).mul(1.0 / 6.0)
+   }
+}
+
+pub struct Cube { pub min: Vec3, pub max: Vec3, pub block: Block }
 
 impl Hittable for Cube {
-   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
       // AABB por slabs
       let inv = Vec3::new(1.0/ray.dir.x, 1.0/ray.dir.y, 1.0/ray.dir.z);
 
@@ -25,43 +127,43 @@ impl Hittable for Cube {
          if !(t > tmin && t < tmax) { return None; }
          let p = ray.at(t);
          let eps = 1e-3;
-         let (n, uv) = if (p.x - self.min.x).abs() < eps {
-               // -X
-               let u = (p.z - self.min.z) / (self.max.z - self.min.z);
-               let v = (p.y - self.min.y) / (self.max.y - self.min.y);
-               (Vec3::new(-1.0,0.0,0.0), (u, v))
-         } else if (p.x - self.max.x).abs() < eps {
-               // +X
-               let u = 1.0 - (p.z - self.min.z) / (self.max.z - self.min.z);
-               let v = (p.y - self.min.y) / (self.max.y - self.min.y);
-               (Vec3::new(1.0,0.0,0.0), (u, v))
-         } else if (p.y - self.min.y).abs() < eps {
-               // -Y
-               let u = (p.x - self.min.x) / (self.max.x - self.min.x);
-               let v = 1.0 - (p.z - self.min.z) / (self.max.z - self.min.z);
-               (Vec3::new(0.0,-1.0,0.0), (u, v))
-         } else if (p.y - self.max.y).abs() < eps {
-               // +Y
-               let u = (p.x - self.min.x) / (self.max.x - self.min.x);
-               let v = (p.z - self.min.z) / (self.max.z - self.min.z);
-               (Vec3::new(0.0,1.0,0.0), (u, v))
-         } else if (p.z - self.min.z).abs() < eps {
-               // -Z
-               let u = (p.x - self.min.x) / (self.max.x - self.min.x);
-               let v = (p.y - self.min.y) / (self.max.y - self.min.y);
-               (Vec3::new(0.0,0.0,-1.0), (u, v))
-         } else {
-               // +Z
-               let u = 1.0 - (p.x - self.min.x) / (self.max.x - self.min.x);
-               let v = (p.y - self.min.y) / (self.max.y - self.min.y);
-               (Vec3::new(0.0,0.0,1.0), (u, v))
-         };
-
-         Some(Hit {
-               t, p, n,
-               mat_ptr: &self.mat as *const Material,
-               uv
-         })
+         let n = if (p.x - self.min.x).abs() < eps { Vec3::new(-1.0,0.0,0.0) }
+         else if (p.x - self.max.x).abs() < eps { Vec3::new(1.0,0.0,0.0) }
+         else if (p.y - self.min.y).abs() < eps { Vec3::new(0.0,-1.0,0.0) }
+         else if (p.y - self.max.y).abs() < eps { Vec3::new(0.0,1.0,0.0) }
+         else if (p.z - self.min.z).abs() < eps { Vec3::new(0.0,0.0,-1.0) }
+         else { Vec3::new(0.0,0.0,1.0) };
+         Some(self.block.hit_at(t, p, n, self.min, self.max))
       } else { None }
    }
+
+   fn bounding_box(&self) -> Option<Aabb> { Some(Aabb::new(self.min, self.max)) }
+
+   fn emitters(&self) -> Vec<AreaLight> {
+      if !self.block.is_emissive() { return Vec::new(); }
+      vec![AreaLight { shape: AreaShape::Box { min: self.min, max: self.max }, radiance: self.block.emitted() }]
+   }
+}
+
+/// UV por cara de una caja alineada (convención compartida por `Cube` y `VoxelGrid`).
+pub fn face_uv(n: Vec3, p: Vec3, min: Vec3, max: Vec3) -> (f32, f32) {
+   let size = max.sub(min);
+   let (lx, ly, lz) = ((p.x - min.x) / size.x, (p.y - min.y) / size.y, (p.z - min.z) / size.z);
+   if n.x < -0.5 { (lz, ly) }                  // -X
+   else if n.x > 0.5 { (1.0 - lz, ly) }        // +X
+   else if n.y < -0.5 { (lx, 1.0 - lz) }       // -Y
+   else if n.y > 0.5 { (lx, lz) }              // +Y
+   else if n.z < -0.5 { (lx, ly) }             // -Z
+   else { (1.0 - lx, ly) }                     // +Z
+}
+
+/// Direcciones de +u / +v en mundo para cada cara (coinciden con `face_uv`).
+pub fn face_tangents(n: Vec3) -> (Vec3, Vec3) {
+   let (x, y, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+   if n.x < -0.5 { (z, y) }                    // -X
+   else if n.x > 0.5 { (z.mul(-1.0), y) }      // +X
+   else if n.y < -0.5 { (x, z.mul(-1.0)) }     // -Y
+   else if n.y > 0.5 { (x, z) }                // +Y
+   else if n.z < -0.5 { (x, y) }               // -Z
+   else { (x.mul(-1.0), y) }                   // +Z
 }
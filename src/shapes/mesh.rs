@@ -0,0 +1,121 @@
+use crate::{math::Vec3, ray::Ray, material::{Material, Hit}, bvh::{Aabb, Bvh}, light::{AreaLight, AreaShape}};
+use super::Hittable;
+
+/// Triángulo indexado: posiciones, y opcionalmente UVs/normales por vértice, más su material.
+#[derive(Copy, Clone, Debug)]
+pub struct Triangle {
+   pub p: [u32; 3],
+   pub uv: Option<[u32; 3]>,
+   pub n: Option<[u32; 3]>,
+   pub mat: u32,
+}
+
+/// Malla de triángulos con BVH propio (Möller–Trumbore por triángulo).
+pub struct TriangleMesh {
+   pub positions: Vec<Vec3>,
+   pub normals: Vec<Vec3>,
+   pub uvs: Vec<(f32, f32)>,
+   pub triangles: Vec<Triangle>,
+   pub materials: Vec<Material>,
+   bvh: Bvh,
+   bounds: Option<Aabb>,
+}
+
+impl TriangleMesh {
+   pub fn new(positions: Vec<Vec3>, normals: Vec<Vec3>, uvs: Vec<(f32, f32)>, triangles: Vec<Triangle>, materials: Vec<Material>) -> Self {
+      let mut mesh = Self { positions, normals, uvs, triangles, materials, bvh: Bvh::build(Vec::new()), bounds: None };
+      mesh.rebuild();
+      mesh
+   }
+
+   /// Escala uniforme + traslación (reconstruye el BVH).
+   pub fn transform(&mut self, scale: f32, offset: Vec3) {
+      for p in &mut self.positions { *p = p.mul(scale).add(offset); }
+      if scale < 0.0 { for n in &mut self.normals { *n = n.mul(-1.0); } }
+      self.rebuild();
+   }
+
+   fn tri_bounds(&self, t: &Triangle) -> Aabb {
+      let [a, b, c] = t.p.map(|i| self.positions[i as usize]);
+      Aabb::new(a, a).grow(b).grow(c)
+   }
+
+   fn rebuild(&mut self) {
+      let items: Vec<(usize, Aabb)> = self.triangles.iter().enumerate().map(|(i, t)| (i, self.tri_bounds(t))).collect();
+      self.bounds = items.iter().map(|(_, bb)| *bb).reduce(Aabb::union);
+      self.bvh = Bvh::build(items);
+   }
+
+   fn hit_triangle(&self, idx: usize, ray: &Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      let tri = &self.triangles[idx];
+      let [p0, p1, p2] = tri.p.map(|i| self.positions[i as usize]);
+
+      // Möller–Trumbore (sin culling: los rayos refractados salen por detrás)
+      let e1 = p1.sub(p0);
+      let e2 = p2.sub(p0);
+      let pv = ray.dir.cross(e2);
+      let det = e1.dot(pv);
+      if det.abs() < 1e-9 { return None; }
+      let inv_det = 1.0 / det;
+      let tv = ray.origin.sub(p0);
+      let b1 = tv.dot(pv) * inv_det;
+      if !(0.0..=1.0).contains(&b1) { return None; }
+      let qv = tv.cross(e1);
+      let b2 = ray.dir.dot(qv) * inv_det;
+      if b2 < 0.0 || b1 + b2 > 1.0 { return None; }
+      let t = e2.dot(qv) * inv_det;
+      if !(t > tmin && t < tmax) { return None; }
+
+      let b0 = 1.0 - b1 - b2;
+      let geo_n = e1.cross(e2).normalize();
+      let n = match tri.n {
+         Some([a, b, c]) => {
+            let (na, nb, nc) = (self.normals[a as usize], self.normals[b as usize], self.normals[c as usize]);
+            let n = na.mul(b0).add(nb.mul(b1)).add(nc.mul(b2)).normalize();
+            if n.length() > 0.0 { n } else { geo_n }
+         }
+         None => geo_n,
+      };
+      // UV interpoladas y tangentes dp/du, dp/dv del triángulo (sin UVs: baricéntricas);
+      // la densidad sale de comparar el área en UV con el área en mundo
+      let world_area = e1.cross(e2).length();
+      let (uv, tangent, bitangent, uv_area) = match tri.uv {
+         Some([a, b, c]) => {
+            let (ua, ub, uc) = (self.uvs[a as usize], self.uvs[b as usize], self.uvs[c as usize]);
+            let uv = (ua.0 * b0 + ub.0 * b1 + uc.0 * b2, ua.1 * b0 + ub.1 * b1 + uc.1 * b2);
+            let (du1, dv1, du2, dv2) = (ub.0 - ua.0, ub.1 - ua.1, uc.0 - ua.0, uc.1 - ua.1);
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() > 1e-12 {
+               let r = 1.0 / det;
+               (uv, e1.mul(dv2).sub(e2.mul(dv1)).mul(r), e2.mul(du1).sub(e1.mul(du2)).mul(r), det.abs())
+            } else { (uv, e1, e2, 1.0) }
+         }
+         None => ((b1, b2), e1, e2, 1.0),
+      };
+
+      Some(Hit {
+         t, p: ray.at(t), n,
+         mat: &self.materials[tri.mat as usize],
+         uv, tangent, bitangent,
+         uv_density: (uv_area / world_area.max(1e-12)).sqrt(),
+      })
+   }
+}
+
+impl Hittable for TriangleMesh {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      self.bvh.trace(ray, tmin, tmax, |i, closest| self.hit_triangle(i, &ray, tmin, closest))
+   }
+
+   fn bounding_box(&self) -> Option<Aabb> { self.bounds }
+
+   // Un emisor por triángulo con material emisivo (`Ke` en el MTL)
+   fn emitters(&self) -> Vec<AreaLight> {
+      self.triangles.iter().filter_map(|t| {
+         let mat = &self.materials[t.mat as usize];
+         if !mat.is_emissive() { return None; }
+         let [a, b, c] = t.p.map(|i| self.positions[i as usize]);
+         Some(AreaLight { shape: AreaShape::Triangle { a, b, c }, radiance: mat.emitted() })
+      }).collect()
+   }
+}
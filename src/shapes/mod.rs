@@ -1,11 +1,22 @@
-use crate::{ray::Ray, material::Hit};
+use crate::{ray::Ray, material::Hit, bvh::Aabb, light::AreaLight};
 
-pub trait Hittable {
-   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit>;
+// Send + Sync: la escena se comparte entre los hilos de render
+pub trait Hittable: Send + Sync {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>>;
+   /// Caja envolvente en mundo; `None` para primitivas infinitas (quedan fuera del BVH).
+   fn bounding_box(&self) -> Option<Aabb>;
+   /// Partes emisivas, para muestrearlas como luces de área (por defecto ninguna).
+   fn emitters(&self) -> Vec<AreaLight> { Vec::new() }
 }
 
 pub mod plane;
 pub mod cube;
+pub mod voxel;
+pub mod sphere;
+pub mod mesh;
 
 pub use plane::Plane;
 pub use cube::Cube;
+pub use voxel::VoxelGrid;
+pub use sphere::Sphere;
+pub use mesh::TriangleMesh;
@@ -1,10 +1,10 @@
-use crate::{math::Vec3, ray::Ray, material::{Material, Hit}};
+use crate::{math::Vec3, ray::Ray, material::{Material, Hit}, bvh::Aabb};
 use super::Hittable;
 
 pub struct Plane { pub y: f32, pub mat: Material }
 
 impl Hittable for Plane {
-   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit> {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
       let denom = ray.dir.y;
       if denom.abs() < 1e-4 { return None; }
       let t = (self.y - ray.origin.y) / denom;
@@ -14,9 +14,15 @@ impl Hittable for Plane {
          Some(Hit {
                t, p,
                n: Vec3::new(0.0, 1.0, 0.0),
-               mat_ptr: &self.mat as *const Material,
-               uv
+               mat: &self.mat,
+               uv,
+               tangent: Vec3::new(1.0, 0.0, 0.0),
+               bitangent: Vec3::new(0.0, 0.0, 1.0),
+               uv_density: 1.0,
          })
       } else { None }
    }
+
+   // Plano infinito: no entra al BVH
+   fn bounding_box(&self) -> Option<Aabb> { None }
 }
@@ -0,0 +1,56 @@
+use std::f32::consts::PI;
+use crate::{math::Vec3, ray::Ray, material::{Material, Hit}, bvh::Aabb, light::{AreaLight, AreaShape}};
+use super::Hittable;
+
+pub struct Sphere { pub center: Vec3, pub radius: f32, pub mat: Material }
+
+impl Hittable for Sphere {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      let oc = ray.origin.sub(self.center);
+      let a = ray.dir.dot(ray.dir);
+      let half_b = oc.dot(ray.dir);
+      let c = oc.dot(oc) - self.radius * self.radius;
+      let disc = half_b * half_b - a * c;
+      if disc < 0.0 { return None; }
+      let sq = disc.sqrt();
+
+      // Raíz cercana; si el origen está dentro (rayo refractado) vale la lejana
+      let mut t = (-half_b - sq) / a;
+      if !(t > tmin && t < tmax) {
+         t = (-half_b + sq) / a;
+         if !(t > tmin && t < tmax) { return None; }
+      }
+
+      let p = ray.at(t);
+      // Normal siempre saliente; trace_color la invierte si el rayo viene de dentro
+      let n = p.sub(self.center).mul(1.0 / self.radius);
+
+      // UV esféricas: u alrededor del eje Y, v = 1 en el polo norte
+      let u = 0.5 + n.z.atan2(n.x) / (2.0 * PI);
+      let v = 1.0 - n.y.clamp(-1.0, 1.0).acos() / PI;
+
+      // Tangente a lo largo de u (paralelos) y bitangente hacia el polo norte (+v)
+      let tangent = Vec3::new(-n.z, 0.0, n.x).normalize();
+      let tangent = if tangent.length() > 0.0 { tangent } else { Vec3::new(1.0, 0.0, 0.0) };
+      let bitangent = tangent.cross(n);
+
+      Some(Hit {
+         t, p, n,
+         mat: &self.mat,
+         uv: (u, v),
+         tangent, bitangent,
+         // u da la vuelta en 2πr y v en πr: media geométrica de las dos densidades
+         uv_density: 1.0 / (PI * self.radius * std::f32::consts::SQRT_2),
+      })
+   }
+
+   fn bounding_box(&self) -> Option<Aabb> {
+      let r = Vec3::new(self.radius, self.radius, self.radius);
+      Some(Aabb::new(self.center.sub(r), self.center.add(r)))
+   }
+
+   fn emitters(&self) -> Vec<AreaLight> {
+      if !self.mat.is_emissive() { return Vec::new(); }
+      vec![AreaLight { shape: AreaShape::Sphere { center: self.center, radius: self.radius }, radiance: self.mat.emitted() }]
+   }
+}
@@ -0,0 +1,263 @@
+use crate::{math::Vec3, ray::Ray, material::{Material, Hit}, bvh::Aabb, light::{AreaLight, AreaShape}};
+use super::{Hittable, cube::Block};
+
+/// Id de bloque dentro de la grilla: 0 = aire, n indexa `blocks[n - 1]`.
+pub type BlockId = u16;
+pub const AIR: BlockId = 0;
+
+/// Mundo de bloques unitarios en una grilla densa 3D, recorrida con 3D-DDA (Amanatides–Woo).
+/// La celda (x,y,z) ocupa [x,x+1]×[y,y+1]×[z,z+1] en mundo.
+pub struct VoxelGrid {
+   origin: [i32; 3],   // celdas reservadas (crecen con holgura)
+   dims: [i32; 3],
+   lo: [i32; 3],       // caja de las celdas colocadas de verdad (la que ve el trazado)
+   hi: [i32; 3],
+   cells: Vec<BlockId>,
+   blocks: Vec<Block>, // paleta: materiales por cara + orientación
+}
+
+// Holgura mínima por lado al crecer
+const SLACK: i32 = 8;
+
+/// Las coordenadas de celda válidas están en [-MAX_COORD, MAX_COORD).
+pub const MAX_COORD: i32 = 1 << 20;
+/// Tope de celdas reservadas (2 bytes cada una): la grilla densa no crece más allá.
+pub const MAX_CELLS: usize = 1 << 27;
+
+impl VoxelGrid {
+   pub fn new() -> Self {
+      Self { origin: [0; 3], dims: [0; 3], lo: [i32::MAX; 3], hi: [i32::MIN; 3], cells: Vec::new(), blocks: Vec::new() }
+   }
+
+   /// Registra un material (igual en las seis caras) y devuelve el id de bloque que lo usa.
+   pub fn add_material(&mut self, mat: Material) -> BlockId { self.add_block(Block::new(mat)) }
+
+   /// Registra un bloque (materiales por cara / orientación); cada orientación es un id distinto.
+   /// Entra en pánico si la paleta se llena (ver `try_add_block`).
+   pub fn add_block(&mut self, block: Block) -> BlockId {
+      self.try_add_block(block).unwrap_or_else(|e| panic!("{e}"))
+   }
+
+   /// Como `add_block`, pero con error si ya hay `BlockId::MAX` bloques en la paleta.
+   pub fn try_add_block(&mut self, block: Block) -> Result<BlockId, String> {
+      if self.blocks.len() >= BlockId::MAX as usize { return Err(format!("demasiados bloques distintos (máximo {})", BlockId::MAX)); }
+      self.blocks.push(block);
+      Ok(self.blocks.len() as BlockId)
+   }
+
+   pub fn get(&self, x: i32, y: i32, z: i32) -> BlockId {
+      match self.index([x, y, z]) { Some(i) => self.cells[i], None => AIR }
+   }
+
+   /// Coloca un bloque; la grilla crece si la celda queda fuera.
+   /// Entra en pánico fuera de los límites (ver `try_set`).
+   pub fn set(&mut self, x: i32, y: i32, z: i32, id: BlockId) {
+      self.try_set(x, y, z, id).unwrap_or_else(|e| panic!("{e}"))
+   }
+
+   /// Como `set`, pero con error si la celda sale de ±`MAX_COORD` o la grilla pasaría de `MAX_CELLS`.
+   pub fn try_set(&mut self, x: i32, y: i32, z: i32, id: BlockId) -> Result<(), String> {
+      debug_assert!((id as usize) <= self.blocks.len(), "id de bloque sin material");
+      let c = [x, y, z];
+      if self.index(c).is_none() {
+         if id == AIR { return Ok(()); }
+         if c.iter().any(|v| !(-MAX_COORD..MAX_COORD).contains(v)) {
+            return Err(format!("celda ({x}, {y}, {z}) fuera de la grilla (coordenadas en ±{MAX_COORD})"));
+         }
+         self.grow_to(c)?;
+      }
+      if id != AIR {
+         for (a, &v) in c.iter().enumerate() {
+            self.lo[a] = self.lo[a].min(v);
+            self.hi[a] = self.hi[a].max(v + 1);
+         }
+      }
+      let i = self.index(c).unwrap();
+      self.cells[i] = id;
+      Ok(())
+   }
+
+   // Sin bloques colocados
+   fn is_empty(&self) -> bool { self.lo[0] >= self.hi[0] }
+
+   fn index(&self, c: [i32; 3]) -> Option<usize> {
+      let mut l = [0usize; 3];
+      for a in 0..3 {
+         let d = c[a] - self.origin[a];
+         if d < 0 || d >= self.dims[a] { return None; }
+         l[a] = d as usize;
+      }
+      Some(l[0] + self.dims[0] as usize * (l[1] + self.dims[1] as usize * l[2]))
+   }
+
+   // Crecimiento geométrico: el eje que se desborda gana al menos su tamaño actual (o SLACK)
+   // del lado de `c`, así llenar una región bloque a bloque copia la grilla O(log n) veces.
+   // La holgura se recorta a ±MAX_COORD y se descarta si pasaría de MAX_CELLS.
+   fn grow_to(&mut self, c: [i32; 3]) -> Result<(), String> {
+      let empty = self.cells.is_empty();
+      let bounds = |slack: bool| {
+         let (mut lo, mut hi) = ([0; 3], [0; 3]);
+         for a in 0..3 {
+            let (o, end, extra) = (self.origin[a], self.origin[a] + self.dims[a], if slack { self.dims[a].max(SLACK) } else { 0 });
+            lo[a] = if empty || c[a] < o { (c[a] - extra).max(-MAX_COORD) } else { o };
+            hi[a] = if empty || c[a] >= end { (c[a] + 1 + extra).min(MAX_COORD) } else { end };
+         }
+         let cells = (0..3).try_fold(1usize, |n, a| n.checked_mul((hi[a] - lo[a]) as usize)).filter(|&n| n <= MAX_CELLS);
+         cells.map(|n| (lo, hi, n))
+      };
+      let (lo, hi, cells) = bounds(true).or_else(|| bounds(false))
+         .ok_or_else(|| format!("la grilla de vóxeles pasaría de {MAX_CELLS} celdas al llegar a ({}, {}, {})", c[0], c[1], c[2]))?;
+      let mut grown = VoxelGrid {
+         origin: lo,
+         dims: [hi[0] - lo[0], hi[1] - lo[1], hi[2] - lo[2]],
+         lo: self.lo,
+         hi: self.hi,
+         cells: vec![AIR; cells],
+         blocks: Vec::new(),
+      };
+      for z in 0..self.dims[2] {
+         for y in 0..self.dims[1] {
+            for x in 0..self.dims[0] {
+               let c = [self.origin[0] + x, self.origin[1] + y, self.origin[2] + z];
+               let id = self.cells[self.index(c).unwrap()];
+               let i = grown.index(c).unwrap();
+               grown.cells[i] = id;
+            }
+         }
+      }
+      self.origin = grown.origin;
+      self.dims = grown.dims;
+      self.cells = grown.cells;
+      Ok(())
+   }
+
+   fn hit_face(&self, ray: &Ray, t: f32, cell: [i32; 3], n: Vec3, id: BlockId) -> Hit<'_> {
+      let min = Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32);
+      self.blocks[id as usize - 1].hit_at(t, ray.at(t), n, min, min.add(Vec3::new(1.0, 1.0, 1.0)))
+   }
+}
+
+impl Default for VoxelGrid {
+   fn default() -> Self { Self::new() }
+}
+
+fn axis_normal(axis: usize, sign: i32) -> Vec3 {
+   let s = sign as f32;
+   match axis { 0 => Vec3::new(s, 0.0, 0.0), 1 => Vec3::new(0.0, s, 0.0), _ => Vec3::new(0.0, 0.0, s) }
+}
+
+impl Hittable for VoxelGrid {
+   fn hit(&self, ray: Ray, tmin: f32, tmax: f32) -> Option<Hit<'_>> {
+      let bb = self.bounding_box()?;
+      let o = [ray.origin.x, ray.origin.y, ray.origin.z];
+      let d = [ray.dir.x, ray.dir.y, ray.dir.z];
+      let (lo, hi) = ([bb.min.x, bb.min.y, bb.min.z], [bb.max.x, bb.max.y, bb.max.z]);
+
+      // Recorte contra la caja de la grilla (recuerdo el eje de entrada para la normal)
+      let mut t_enter = tmin;
+      let mut t_exit = tmax;
+      let mut entry_axis = None;
+      for a in 0..3 {
+         let inv = 1.0 / d[a];
+         let (t1, t2) = ((lo[a] - o[a]) * inv, (hi[a] - o[a]) * inv);
+         let (near, far) = (t1.min(t2), t1.max(t2));
+         if near > t_enter { t_enter = near; entry_axis = Some(a); }
+         t_exit = t_exit.min(far);
+      }
+      if t_enter > t_exit { return None; }
+
+      let mut step = [0i32; 3];
+      let mut cell = [0i32; 3];
+      let mut t_next = [f32::INFINITY; 3];
+      let mut t_delta = [f32::INFINITY; 3];
+      let p = ray.at(t_enter);
+      let p = [p.x, p.y, p.z];
+      for a in 0..3 {
+         cell[a] = (p[a].floor() as i32).clamp(self.lo[a], self.hi[a] - 1);
+         if d[a] > 0.0 {
+            step[a] = 1;
+            t_next[a] = ((cell[a] + 1) as f32 - o[a]) / d[a];
+            t_delta[a] = 1.0 / d[a];
+         } else if d[a] < 0.0 {
+            step[a] = -1;
+            t_next[a] = (cell[a] as f32 - o[a]) / d[a];
+            t_delta[a] = -1.0 / d[a];
+         }
+      }
+
+      // Medio de partida: aire si el rayo entra desde fuera; si nace dentro de un bloque
+      // transparente (p.ej. rayo refractado en agua) el hit es la cara por la que sale de ese medio.
+      // Los demás bloques no son medio: tras saltar un texel recortado de una hoja, la próxima
+      // celda de hoja es un hit nuevo (su cara de entrada), no el final de toda la copa.
+      let medium = |id: BlockId| if id != AIR && self.blocks[id as usize - 1].is_medium() { id } else { AIR };
+      let mut start = if entry_axis.is_some() { AIR } else { medium(self.get(cell[0], cell[1], cell[2])) };
+      let mut t = t_enter;
+      let mut last_axis = entry_axis;
+
+      loop {
+         let id = self.get(cell[0], cell[1], cell[2]);
+         if id != start && t <= tmin {
+            // Cruce pegado al origen (rayo nacido justo sobre una cara): ya estamos en ese medio
+            start = medium(id);
+         } else if id != start {
+            let axis = last_axis?;
+            if t >= tmax { return None; }
+            return Some(if start == AIR {
+               // Entra a un bloque: normal opuesta al paso
+               self.hit_face(&ray, t, cell, axis_normal(axis, -step[axis]), id)
+            } else {
+               // Sale del bloque de partida: normal saliente de la celda anterior
+               let mut prev = cell;
+               prev[axis] -= step[axis];
+               self.hit_face(&ray, t, prev, axis_normal(axis, step[axis]), start)
+            });
+         }
+
+         // Avanzo por el eje con el próximo cruce más cercano
+         let axis = if t_next[0] < t_next[1] {
+            if t_next[0] < t_next[2] { 0 } else { 2 }
+         } else if t_next[1] < t_next[2] { 1 } else { 2 };
+         t = t_next[axis];
+         if t > t_exit || t >= tmax {
+            // Sale de la grilla: solo hay hit si veníamos dentro de un bloque
+            if start != AIR && t_exit < tmax && t_exit > tmin {
+               return Some(self.hit_face(&ray, t_exit, cell, axis_normal(axis, step[axis]), start));
+            }
+            return None;
+         }
+         cell[axis] += step[axis];
+         t_next[axis] += t_delta[axis];
+         last_axis = Some(axis);
+      }
+   }
+
+   // Grilla vacía: sin caja; la holgura reservada no cuenta
+   fn bounding_box(&self) -> Option<Aabb> {
+      if self.is_empty() { return None; }
+      let min = Vec3::new(self.lo[0] as f32, self.lo[1] as f32, self.lo[2] as f32);
+      let max = Vec3::new(self.hi[0] as f32, self.hi[1] as f32, self.hi[2] as f32);
+      Some(Aabb::new(min, max))
+   }
+
+   // Un emisor por bloque emisivo con al menos una cara al aire
+   fn emitters(&self) -> Vec<AreaLight> {
+      let mut out = Vec::new();
+      for gz in self.lo[2]..self.hi[2] {
+         for gy in self.lo[1]..self.hi[1] {
+            for gx in self.lo[0]..self.hi[0] {
+               let id = self.get(gx, gy, gz);
+               if id == AIR || !self.blocks[id as usize - 1].is_emissive() { continue; }
+               let exposed = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)]
+                  .iter().any(|&(dx, dy, dz)| self.get(gx + dx, gy + dy, gz + dz) == AIR);
+               if !exposed { continue; }
+               let min = Vec3::new(gx as f32, gy as f32, gz as f32);
+               out.push(AreaLight {
+                  shape: AreaShape::Box { min, max: min.add(Vec3::new(1.0, 1.0, 1.0)) },
+                  radiance: self.blocks[id as usize - 1].emitted(),
+               });
+            }
+         }
+      }
+      out
+   }
+}
@@ -1,38 +1,157 @@
+// ==========================================================
+// Skybox equirectangular en float (LDR vía raylib o HDR Radiance .hdr)
+// ==========================================================
+// Los píxeles se guardan como radiancia lineal sin límite: un .hdr puede traer un sol
+// de miles de unidades y así el IBL (`lighting::diffuse_env`) tiene rango dinámico real.
 use raylib::prelude::*;
-use crate::math::Vec3;
+use crate::{math::Vec3, color::decode8};
 
 pub struct Skybox {
    pub width: i32,
    pub height: i32,
-   pub pixels: Vec<Color>,
+   pub pixels: Vec<Vec3>, // radiancia lineal (sRGB decodificado o float del .hdr)
+   pub intensity: f32,    // multiplicador de la radiancia
+   pub rotation: f32,     // giro alrededor de +Y (radianes)
 }
 
 impl Skybox {
-   // Carga directa desde archivo a RAM (no crea Texture2D).
-   pub fn load(path: &str) -> Option<Self> {
-      let img = Image::load_image(path).ok()?;
-      let width = img.width();
-      let height = img.height();
-
-      // Extrae colores a CPU
-      let colors = img.get_image_data();      // ImageColors
-      let slice: &[Color] = colors.as_ref().as_ref();
-      Some(Self { width, height, pixels: slice.to_vec() })
+   // Carga directa desde archivo a RAM (no crea Texture2D). `.hdr` usa el lector RGBE propio;
+   // lo demás pasa por raylib como imagen sRGB de 8 bits.
+   pub fn load(path: &str) -> Result<Self, String> {
+      let ext = path.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+      match ext.as_str() {
+         "hdr" | "pic" => {
+            let bytes = std::fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+            let (width, height, pixels) = decode_rgbe(&bytes).map_err(|e| format!("{path}: {e}"))?;
+            Ok(Self::from_pixels(width, height, pixels))
+         }
+         "exr" => Err(format!("{path}: OpenEXR no está soportado (convertilo a Radiance .hdr)")),
+         _ => {
+            let img = Image::load_image(path).map_err(|_| format!("No se encontró {path}"))?;
+            let (width, height) = (img.width(), img.height());
+
+            // Extrae colores a CPU
+            let colors = img.get_image_data();      // ImageColors
+            let slice: &[Color] = colors.as_ref().as_ref();
+            let pixels = slice.iter().map(|c| Vec3::new(decode8(c.r), decode8(c.g), decode8(c.b))).collect();
+            Ok(Self::from_pixels(width, height, pixels))
+         }
+      }
+   }
+
+   pub fn from_pixels(width: i32, height: i32, pixels: Vec<Vec3>) -> Self {
+      Self { width, height, pixels, intensity: 1.0, rotation: 0.0 }
    }
 
    // Mapea dirección -> (u,v) equirectangulares y muestrea.
    pub fn sample_dir(&self, dir: Vec3) -> Vec3 {
       let d = dir.normalize();
-      let u = 0.5 + 0.5 * d.z.atan2(d.x) / std::f32::consts::PI;
+      let u = 0.5 + 0.5 * d.z.atan2(d.x) / std::f32::consts::PI + 0.5 * self.rotation / std::f32::consts::PI;
       let v = (d.y.clamp(-1.0, 1.0)).acos() / std::f32::consts::PI;
 
-      let x = (u * self.width as f32) as i32;
+      let x = (u * self.width as f32).floor() as i32;
       let y = (v * self.height as f32) as i32;
       let xi = x.rem_euclid(self.width);
       let yi = y.clamp(0, self.height - 1);
 
       let idx = (yi as usize) * (self.width as usize) + (xi as usize);
-      let c = self.pixels[idx];
-      Vec3::new(c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0)
+      self.pixels[idx].mul(self.intensity)
    }
 }
+
+// Tope de compresión aceptado al validar las dimensiones contra el tamaño del archivo
+const MAX_PIXELS_PER_BYTE: usize = 32;
+
+// ----------------------------------------------------------
+// Radiance RGBE (.hdr): cabecera de texto, línea de resolución y scanlines
+// planas, RLE viejo (1,1,1,n) o RLE nuevo por canal (2,2,hi,lo)
+// ----------------------------------------------------------
+fn decode_rgbe(bytes: &[u8]) -> Result<(i32, i32, Vec<Vec3>), String> {
+   let mut pos = 0;
+   let mut line = || -> Result<&str, String> {
+      let start = pos;
+      let len = bytes[start..].iter().position(|&b| b == b'\n').ok_or("cabecera HDR truncada")?;
+      pos = start + len + 1;
+      std::str::from_utf8(&bytes[start..start + len]).map_err(|_| "cabecera HDR inválida".to_string())
+   };
+
+   if !line()?.starts_with("#?") { return Err("no es un archivo Radiance (falta #?RADIANCE)".into()); }
+   loop {
+      let l = line()?;
+      if l.is_empty() { break; }
+      if let Some(fmt) = l.strip_prefix("FORMAT=").filter(|&f| f != "32-bit_rle_rgbe") {
+         return Err(format!("formato HDR no soportado: {fmt}"));
+      }
+   }
+   // Solo la orientación estándar: filas de arriba hacia abajo, columnas de izquierda a derecha
+   let res: Vec<&str> = line()?.split_whitespace().collect();
+   let (height, width) = match res.as_slice() {
+      ["-Y", h, "+X", w] => (h.parse::<usize>().map_err(|_| "alto HDR inválido")?, w.parse::<usize>().map_err(|_| "ancho HDR inválido")?),
+      _ => return Err(format!("orientación HDR no soportada: {}", res.join(" "))),
+   };
+   if width == 0 || height == 0 { return Err("imagen HDR vacía".into()); }
+
+   // El RLE nuevo comprime a lo sumo ~16 píxeles por byte (corridas de 127 en 2 bytes por canal):
+   // una cabecera que pide muchos más píxeles que eso está rota y no se reserva memoria para ella
+   let mut data = &bytes[pos..];
+   let pixel_count = width.checked_mul(height).ok_or("dimensiones HDR inválidas")?;
+   if pixel_count / MAX_PIXELS_PER_BYTE > data.len() || height.saturating_mul(4) > data.len() {
+      return Err(format!("{width}x{height} no entra en {} bytes de datos HDR", data.len()));
+   }
+   let mut next = || -> Result<u8, String> {
+      let (&b, rest) = data.split_first().ok_or("datos HDR truncados")?;
+      data = rest;
+      Ok(b)
+   };
+   let mut pixels = Vec::with_capacity(pixel_count);
+   let mut scan = vec![[0u8; 4]; width];
+   for _ in 0..height {
+      let first = [next()?, next()?, next()?, next()?];
+      if (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2 && first[2] & 0x80 == 0 {
+         // RLE nuevo: cada canal por separado, corridas (>128) o literales
+         if ((first[2] as usize) << 8 | first[3] as usize) != width { return Err("ancho de scanline HDR incoherente".into()); }
+         for ch in 0..4 {
+            let mut x = 0;
+            while x < width {
+               let count = next()? as usize;
+               let (run, n) = if count > 128 { (true, count - 128) } else { (false, count) };
+               if n == 0 || x + n > width { return Err("corrida RLE fuera de la scanline".into()); }
+               let v = if run { next()? } else { 0 };
+               for px in &mut scan[x..x + n] { px[ch] = if run { v } else { next()? }; }
+               x += n;
+            }
+         }
+      } else {
+         // Plano (con el RLE viejo: 1,1,1,n repite el píxel anterior)
+         let (mut x, mut shift, mut px) = (0, 0, first);
+         loop {
+            if px[0] == 1 && px[1] == 1 && px[2] == 1 {
+               if x == 0 { return Err("RLE viejo sin píxel previo".into()); }
+               // Registros seguidos multiplican por 256; más de tres no caben en ninguna scanline real
+               if px[3] == 0 || shift > 16 { return Err("corrida RLE vieja inválida".into()); }
+               let n = (px[3] as usize) << shift;
+               if x + n > width { return Err("corrida RLE fuera de la scanline".into()); }
+               let prev = scan[x - 1];
+               scan[x..x + n].fill(prev);
+               x += n;
+               shift += 8;
+            } else {
+               scan[x] = px;
+               x += 1;
+               shift = 0;
+            }
+            if x >= width { break; }
+            px = [next()?, next()?, next()?, next()?];
+         }
+      }
+      pixels.extend(scan.iter().map(|&p| rgbe_to_vec3(p)));
+   }
+   Ok((width as i32, height as i32, pixels))
+}
+
+// Mantisas de 8 bits con exponente compartido: c · 2^(e-136)
+fn rgbe_to_vec3([r, g, b, e]: [u8; 4]) -> Vec3 {
+   if e == 0 { return Vec3::new(0.0, 0.0, 0.0); }
+   let f = (e as f32 - 136.0).exp2();
+   Vec3::new(r as f32 * f, g as f32 * f, b as f32 * f)
+}
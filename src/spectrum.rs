@@ -0,0 +1,30 @@
+// ==========================================================
+// Dispersión: índice de refracción por longitud de onda (Cauchy)
+// ==========================================================
+// Al cruzar el primer material dispersivo, el camino se divide en tres: cada canal
+// (R, G, B) sigue con una λ elegida al azar dentro de su banda y aporta solo su
+// componente. Al acumular frames las bandas se cubren enteras y aparece el "fuego".
+use crate::lighting::Rng;
+
+// Líneas de Fraunhofer usadas en la definición del número de Abbe (nm)
+const LAMBDA_D: f32 = 587.6;
+const LAMBDA_F: f32 = 486.1;
+const LAMBDA_C: f32 = 656.3;
+
+// Bandas (nm) que representan cada canal RGB
+const BANDS: [(f32, f32); 3] = [(600.0, 700.0), (500.0, 600.0), (400.0, 500.0)];
+
+/// n(λ) = A + B/λ² ajustado para que n(λ_D) = `ior` y (n_D − 1)/(n_F − n_C) = `abbe`.
+pub fn cauchy_ior(ior: f32, abbe: f32, lambda_nm: f32) -> f32 {
+   let um = |l: f32| l * 1e-3;
+   let inv2 = |l: f32| 1.0 / (um(l) * um(l));
+   let b = (ior - 1.0) / (abbe * (inv2(LAMBDA_F) - inv2(LAMBDA_C)));
+   let a = ior - b * inv2(LAMBDA_D);
+   a + b * inv2(lambda_nm)
+}
+
+/// λ al azar dentro de la banda del canal `c` (0 = R, 1 = G, 2 = B).
+pub fn sample_wavelength(c: usize, rng: &mut Rng) -> f32 {
+   let (lo, hi) = BANDS[c];
+   lo + (hi - lo) * rng.next_f32()
+}
@@ -1,35 +1,231 @@
-use crate::math::Vec3;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use crate::{math::Vec3, noise::{Noise, Space}, color::{decode8, linear_to_srgb}};
 use raylib::prelude::Color;
 
+/// Filtrado de las texturas de imagen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Filter {
+   #[default]
+   Nearest,   // texel más cercano (look pixel-art)
+   Bilinear,  // 4 texels del nivel 0
+   Trilinear, // bilineal en dos niveles de mip según el footprint
+}
+
+/// Tamaño aproximado del píxel proyectado sobre la superficie: en mundo (texturas sólidas)
+/// y en UV (imágenes y ruido en UV); difieren según cuánta UV cubre cada unidad de la forma.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Footprint { pub world: f32, pub uv: f32 }
+
+/// Texel RGBA en float lineal.
+pub type Texel = [f32; 4];
+
+/// Un nivel de la cadena de mips.
+#[derive(Debug)]
+pub struct Mip {
+   pub width: i32,
+   pub height: i32,
+   pub pixels: Vec<Texel>,
+}
+
+/// Píxeles de una imagen cargada (nivel 0 + mips 2×2 hasta 1×1), ya en lineal;
+/// se comparten vía `Arc` entre todos los materiales que la usan.
+#[derive(Debug)]
+pub struct ImageData {
+   pub levels: Vec<Mip>,
+   pub srgb: bool, // ¿se decodificó desde sRGB? (los datos no-color se guardan tal cual)
+   undecoded: OnceLock<Arc<ImageData>>, // versión sin decodificar (para `as_data`), se arma una vez
+}
+
+fn rgb(t: Texel) -> Vec3 { Vec3::new(t[0], t[1], t[2]) }
+
+/// Rectángulo en píxeles del nivel 0 (una baldosa de un atlas o la imagen entera).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect { pub x: i32, pub y: i32, pub w: i32, pub h: i32 }
+
+impl Rect {
+   // El mismo rectángulo en el nivel de mip `l` (mínimo 1×1)
+   fn level(self, l: usize) -> Rect {
+      Rect { x: self.x >> l, y: self.y >> l, w: (self.w >> l).max(1), h: (self.h >> l).max(1) }
+   }
+}
+
+impl Mip {
+   // Texel (u repite, v se recorta), v=0 abajo
+   fn at(&self, x: i32, y: i32) -> Texel {
+      let xi = x.rem_euclid(self.width) as usize;
+      let yi = y.clamp(0, self.height - 1) as usize;
+      self.pixels[yi * (self.width as usize) + xi]
+   }
+
+   // Texel dentro de `r`: en una baldosa repite en ambos ejes sin salirse de ella;
+   // la imagen entera conserva la convención de `at` (v se recorta)
+   fn texel(&self, x: i32, y: i32, r: Rect) -> Texel {
+      let whole = r.x == 0 && r.y == 0 && r.w == self.width && r.h == self.height;
+      if whole { return self.at(x, y); }
+      self.at(r.x + x.rem_euclid(r.w), r.y + y.rem_euclid(r.h))
+   }
+
+   fn nearest(&self, uv: (f32, f32), r: Rect) -> Texel {
+      let u = uv.0 - uv.0.floor();
+      let v = uv.1 - uv.1.floor();
+      self.texel((u * r.w as f32) as i32, ((1.0 - v) * r.h as f32) as i32, r)
+   }
+
+   fn bilinear(&self, uv: (f32, f32), r: Rect) -> Vec3 {
+      let u = uv.0 - uv.0.floor();
+      let v = uv.1 - uv.1.floor();
+      let x = u * r.w as f32 - 0.5;
+      let y = (1.0 - v) * r.h as f32 - 0.5;
+      let (x0, y0) = (x.floor(), y.floor());
+      let (fx, fy) = (x - x0, y - y0);
+      let (x0, y0) = (x0 as i32, y0 as i32);
+      let top = rgb(self.texel(x0, y0, r)).mul(1.0 - fx).add(rgb(self.texel(x0 + 1, y0, r)).mul(fx));
+      let bot = rgb(self.texel(x0, y0 + 1, r)).mul(1.0 - fx).add(rgb(self.texel(x0 + 1, y0 + 1, r)).mul(fx));
+      top.mul(1.0 - fy).add(bot.mul(fy))
+   }
+
+   // Siguiente nivel: promedio (en lineal) de bloques 2×2; los lados impares repiten el último texel
+   fn downsample(&self) -> Mip {
+      let (w, h) = ((self.width / 2).max(1), (self.height / 2).max(1));
+      let mut pixels = Vec::with_capacity((w * h) as usize);
+      for y in 0..h {
+         for x in 0..w {
+            let (sx, sy) = ((2 * x).min(self.width - 1), (2 * y).min(self.height - 1));
+            let (sx1, sy1) = ((sx + 1).min(self.width - 1), (sy + 1).min(self.height - 1));
+            let px = [self.at(sx, sy), self.at(sx1, sy), self.at(sx, sy1), self.at(sx1, sy1)];
+            pixels.push(std::array::from_fn(|c| px.iter().map(|t| t[c]).sum::<f32>() * 0.25));
+         }
+      }
+      Mip { width: w, height: h, pixels }
+   }
+}
+
+impl ImageData {
+   /// Desde píxeles de 8 bits: `srgb` decodifica el color a lineal (el alfa siempre es lineal).
+   pub fn new(width: i32, height: i32, pixels: &[Color], srgb: bool) -> Self {
+      let ch = |v: u8| if srgb { decode8(v) } else { v as f32 / 255.0 };
+      let texels = pixels.iter().map(|c| [ch(c.r), ch(c.g), ch(c.b), c.a as f32 / 255.0]).collect();
+      Self { srgb, ..Self::from_texels(width, height, texels) }
+   }
+
+   /// Desde texels ya lineales (p.ej. un normal map generado).
+   pub fn from_texels(width: i32, height: i32, pixels: Vec<Texel>) -> Self {
+      let mut levels = vec![Mip { width, height, pixels }];
+      while let Some(last) = levels.last().filter(|m| m.width > 1 || m.height > 1) {
+         let next = last.downsample();
+         levels.push(next);
+      }
+      Self { levels, srgb: false, undecoded: OnceLock::new() }
+   }
+
+   // Copia con los bytes originales (sin decodificar), mips rehechos. `decode8` es biyectiva
+   // sobre los 256 valores, así que recodificar y redondear recupera el byte exacto.
+   fn undecoded(&self) -> Arc<ImageData> {
+      self.undecoded.get_or_init(|| {
+         let l0 = &self.levels[0];
+         let byte = |v: f32| (linear_to_srgb(v) * 255.0).round() / 255.0;
+         let texels = l0.pixels.iter().map(|t| [byte(t[0]), byte(t[1]), byte(t[2]), t[3]]).collect();
+         Arc::new(ImageData::from_texels(l0.width, l0.height, texels))
+      }).clone()
+   }
+
+   /// Imagen completa como rectángulo.
+   pub fn full(&self) -> Rect { Rect { x: 0, y: 0, w: self.levels[0].width, h: self.levels[0].height } }
+
+   fn sample(&self, uv: (f32, f32), r: Rect, footprint: f32, filter: Filter) -> Vec3 {
+      match filter {
+         Filter::Nearest => rgb(self.levels[0].nearest(uv, r)),
+         Filter::Bilinear => self.levels[0].bilinear(uv, r),
+         Filter::Trilinear => {
+            // footprint en UV → texels del nivel 0 → nivel de mip; en un atlas no se baja
+            // más allá del nivel donde la baldosa queda en 1 texel (no mezcla vecinas)
+            let texels = footprint * r.w.max(r.h) as f32;
+            let max_lod = ((self.levels.len() - 1) as f32).min((r.w.min(r.h) as f32).log2().floor());
+            let lod = texels.max(1.0).log2().min(max_lod);
+            let l0 = lod.floor() as usize;
+            let l1 = (l0 + 1).min(max_lod as usize);
+            let t = lod - l0 as f32;
+            self.levels[l0].bilinear(uv, r.level(l0)).mul(1.0 - t).add(self.levels[l1].bilinear(uv, r.level(l1)).mul(t))
+         }
+      }
+   }
+}
+
+// Clonar una Texture solo copia punteros (Arc), nunca los píxeles
 #[derive(Debug, Clone)]
 pub enum Texture {
    None,
    Checker { scale: f32, a: Vec3, b: Vec3 },
-   Image { width: i32, height: i32, pixels: Vec<Color> },
+   Image { data: Arc<ImageData>, filter: Filter },
+   // Sub-rectángulo (baldosa) de una imagen compartida, p.ej. un terrain sheet
+   AtlasRegion { data: Arc<ImageData>, rect: Rect, filter: Filter },
+   // Ruido procedural evaluado en mundo o en UV (sin archivo)
+   Noise(Arc<Noise>),
    // Atlas de bloque: top / side / bottom
-   BlockAtlas { top: Box<Texture>, side: Box<Texture>, bottom: Box<Texture> },
+   BlockAtlas { top: Arc<Texture>, side: Arc<Texture>, bottom: Arc<Texture> },
+   // Atlas de seis caras: +X, -X, +Y, -Y, +Z, -Z (este, oeste, arriba, abajo, sur, norte)
+   FaceAtlas([Arc<Texture>; 6]),
+}
+
+/// Índice de cara (+X, -X, +Y, -Y, +Z, -Z) según el eje dominante de la normal.
+pub fn face_index(n: Vec3) -> usize {
+   let (ax, ay, az) = (n.x.abs(), n.y.abs(), n.z.abs());
+   if ax >= ay && ax >= az { if n.x > 0.0 { 0 } else { 1 } }
+   else if ay >= az { if n.y > 0.0 { 2 } else { 3 } }
+   else if n.z > 0.0 { 4 } else { 5 }
+}
+
+// Caché global de imágenes por ruta: cada archivo se decodifica una sola vez
+fn image_cache() -> &'static Mutex<HashMap<String, Arc<ImageData>>> {
+   static CACHE: OnceLock<Mutex<HashMap<String, Arc<ImageData>>>> = OnceLock::new();
+   CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
 impl Texture {
    /// Sampling normal (textura única)
    pub fn sample(&self, uv: (f32, f32)) -> Vec3 {
-      self.sample_impl(uv)
+      self.sample_impl(uv, Vec3::new(uv.0, uv.1, 0.0), Footprint::default())
    }
 
-   /// Sampling eligiendo top/side/bottom según la normal (n.y)
-   pub fn sample_with_normal(&self, uv: (f32, f32), n: Vec3) -> Vec3 {
-      match self {
-         Texture::BlockAtlas { top, side, bottom } => {
-               let chosen = if n.y > 0.5 { top.as_ref() }
-               else if n.y < -0.5 { bottom.as_ref() }
-               else { side.as_ref() };
-               chosen.sample_impl(uv)
-         }
-         _ => self.sample_impl(uv),
+   /// Sampling eligiendo la cara del atlas según la normal (top/side/bottom o las seis caras).
+   /// `p`: punto de impacto en mundo (lo usan las texturas procedurales sólidas).
+   /// `footprint`: tamaño del píxel (elige el mip en Trilinear y las octavas del ruido).
+   pub fn sample_with_normal(&self, uv: (f32, f32), p: Vec3, n: Vec3, footprint: Footprint) -> Vec3 {
+      self.face(face_index(n)).sample_impl(uv, p, footprint)
+   }
+
+   /// Alfa en [0,1] (solo las imágenes lo tienen; el resto es opaco).
+   pub fn alpha_with_normal(&self, uv: (f32, f32), n: Vec3) -> f32 {
+      match self.face(face_index(n)) {
+         Texture::Image { data, .. } => data.levels[0].nearest(uv, data.full())[3],
+         Texture::AtlasRegion { data, rect, .. } => data.levels[0].nearest(uv, *rect)[3],
+         _ => 1.0,
       }
    }
 
-   fn sample_impl(&self, uv: (f32, f32)) -> Vec3 {
+   // Sub-textura de un atlas para la cara `i` (ver `face_index`); el resto se devuelve igual.
+   // Si la entrada es a su vez un atlas se sigue bajando, así nunca devuelve un atlas.
+   fn face(&self, i: usize) -> &Texture {
+      let mut t = self;
+      loop {
+         t = match t {
+            Texture::BlockAtlas { top, side, bottom } => match i {
+               2 => top.as_ref(),
+               3 => bottom.as_ref(),
+               _ => side.as_ref(),
+            },
+            Texture::FaceAtlas(faces) => faces[i].as_ref(),
+            other => return other,
+         };
+      }
+   }
+
+   /// Copia de la sub-textura de la cara `i`: los bloques resuelven el atlas una vez,
+   /// en su marco local, y así siguen bien orientados aunque estén rotados.
+   pub fn for_face(&self, i: usize) -> Texture { self.face(i).clone() }
+
+   fn sample_impl(&self, uv: (f32, f32), p: Vec3, footprint: Footprint) -> Vec3 {
       match self {
          Texture::None => Vec3::new(1.0, 1.0, 1.0),
          Texture::Checker { scale, a, b } => {
@@ -39,37 +235,140 @@ impl Texture {
                let iv = v.floor() as i32;
                if ((iu + iv) & 1) == 0 { *a } else { *b }
          }
-         Texture::Image { width, height, pixels } => {
-               let mut u = uv.0 - uv.0.floor();
-               let mut v = uv.1 - uv.1.floor();
-               if u < 0.0 { u += 1.0; }
-               if v < 0.0 { v += 1.0; }
-               let x = (u * (*width as f32)) as i32;
-               let y = ((1.0 - v) * (*height as f32)) as i32;
-               let xi = x.rem_euclid(*width).max(0) as usize;
-               let yi = y.clamp(0, *height - 1) as usize;
-               let idx = yi * (*width as usize) + xi;
-               let c = pixels[idx];
-               Vec3::new(c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0)
+         Texture::Image { data, filter } => data.sample(uv, data.full(), footprint.uv, *filter),
+         Texture::AtlasRegion { data, rect, filter } => data.sample(uv, *rect, footprint.uv, *filter),
+         Texture::Noise(noise) => noise.sample(uv, p, match noise.space { Space::World => footprint.world, Space::Uv => footprint.uv }),
+         // Sin normal no hay cara: se usa la de arriba
+         Texture::BlockAtlas { .. } | Texture::FaceAtlas(_) => self.face(2).sample_impl(uv, p, footprint),
+      }
+   }
+
+   /// Misma textura con otro filtro (se aplica también a las caras de un atlas).
+   pub fn with_filter(self, filter: Filter) -> Self {
+      match self {
+         Texture::Image { data, .. } => Texture::Image { data, filter },
+         Texture::AtlasRegion { data, rect, .. } => Texture::AtlasRegion { data, rect, filter },
+         Texture::BlockAtlas { top, side, bottom } => Texture::BlockAtlas {
+            top: Arc::new(top.as_ref().clone().with_filter(filter)),
+            side: Arc::new(side.as_ref().clone().with_filter(filter)),
+            bottom: Arc::new(bottom.as_ref().clone().with_filter(filter)),
+         },
+         Texture::FaceAtlas(faces) => Texture::FaceAtlas(faces.map(|f| Arc::new(f.as_ref().clone().with_filter(filter)))),
+         other => other,
+      }
+   }
+
+   /// Misma imagen leída como datos (normal maps): deshace la decodificación sRGB del color.
+   /// Las texturas que no son imágenes sRGB se devuelven igual.
+   pub fn as_data(&self) -> Self {
+      match self {
+         Texture::Image { data, filter } if data.srgb => Texture::Image { data: data.undecoded(), filter: *filter },
+         Texture::AtlasRegion { data, rect, filter } if data.srgb => Texture::AtlasRegion { data: data.undecoded(), rect: *rect, filter: *filter },
+         Texture::BlockAtlas { top, side, bottom } => Texture::BlockAtlas {
+            top: Arc::new(top.as_data()),
+            side: Arc::new(side.as_data()),
+            bottom: Arc::new(bottom.as_data()),
+         },
+         Texture::FaceAtlas(faces) => Texture::FaceAtlas(faces.clone().map(|f| Arc::new(f.as_data()))),
+         other => other.clone(),
+      }
+   }
+
+   /// Normal map en espacio tangente derivado de la luminancia (relieve) de una imagen.
+   /// `strength` escala la pendiente; las texturas sin píxeles devuelven `None`.
+   pub fn normal_map_from_height(&self, strength: f32) -> Option<Self> {
+      match self {
+         Texture::Image { data, filter } | Texture::AtlasRegion { data, filter, .. } => {
+            let r = match self { Texture::AtlasRegion { rect, .. } => *rect, _ => data.full() };
+            let src = &data.levels[0];
+            let h = |x: i32, y: i32| { let c = rgb(src.texel(x, y, r)); 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z };
+            let mut pixels = Vec::with_capacity((r.w * r.h) as usize);
+            for y in 0..r.h {
+               for x in 0..r.w {
+                  // Diferencias centrales; la fila 0 de la imagen es v=1 (arriba)
+                  let du = (h(x + 1, y) - h(x - 1, y)) * 0.5 * strength;
+                  let dv = (h(x, y - 1) - h(x, y + 1)) * 0.5 * strength;
+                  let n = Vec3::new(-du, -dv, 1.0).normalize();
+                  pixels.push([n.x * 0.5 + 0.5, n.y * 0.5 + 0.5, n.z * 0.5 + 0.5, 1.0]);
+               }
+            }
+            Some(Texture::Image { data: Arc::new(ImageData::from_texels(r.w, r.h, pixels)), filter: *filter })
+         }
+         Texture::BlockAtlas { top, side, bottom } => Some(Texture::BlockAtlas {
+            top: Arc::new(top.normal_map_from_height(strength)?),
+            side: Arc::new(side.normal_map_from_height(strength)?),
+            bottom: Arc::new(bottom.normal_map_from_height(strength)?),
+         }),
+         Texture::FaceAtlas(faces) => {
+            let mut out = Vec::with_capacity(6);
+            for f in faces { out.push(Arc::new(f.normal_map_from_height(strength)?)); }
+            Some(Texture::FaceAtlas(out.try_into().ok()?))
          }
-         Texture::BlockAtlas { .. } => unreachable!(),
+         _ => None,
       }
    }
 
+   /// Carga una imagen (deduplicada por ruta: la segunda vez devuelve el mismo `Arc`).
    pub fn from_file(path: &str) -> Option<Self> {
+      let filter = Filter::default();
+      if let Some(data) = image_cache().lock().unwrap().get(path) { return Some(Texture::Image { data: data.clone(), filter }); }
+
       let img = raylib::prelude::Image::load_image(path).ok()?;
       let width = img.width();
       let height = img.height();
       let colors = img.get_image_data();
       let slice: &[Color] = colors.as_ref().as_ref();
-      Some(Texture::Image { width, height, pixels: slice.to_vec() })
+      let data = Arc::new(ImageData::new(width, height, slice, true));
+      image_cache().lock().unwrap().insert(path.to_string(), data.clone());
+      Some(Texture::Image { data, filter })
+   }
+
+   /// Sub-rectángulo de una imagen o de una región (píxeles relativos a ella, origen arriba a la
+   /// izquierda); comparte los píxeles. `None` si no es una imagen o el rectángulo se sale.
+   pub fn region(&self, rect: Rect) -> Option<Self> {
+      let (data, src, filter) = self.pixel_bounds()?;
+      let inside = rect.w > 0 && rect.h > 0 && rect.x >= 0 && rect.y >= 0 && rect.x + rect.w <= src.w && rect.y + rect.h <= src.h;
+      inside.then(|| Texture::AtlasRegion { data: data.clone(), rect: Rect { x: src.x + rect.x, y: src.y + rect.y, ..rect }, filter })
+   }
+
+   /// Baldosa `index` de una grilla `cols`×`rows`, contando por filas desde arriba a la izquierda.
+   pub fn tile(&self, cols: i32, rows: i32, index: i32) -> Option<Self> {
+      let (_, src, _) = self.pixel_bounds()?;
+      if cols <= 0 || rows <= 0 || index < 0 || index >= cols * rows { return None; }
+      let (w, h) = (src.w / cols, src.h / rows);
+      self.region(Rect { x: (index % cols) * w, y: (index / cols) * h, w, h })
+   }
+
+   // Imagen y rectángulo que ocupa la textura (la imagen entera o la región)
+   fn pixel_bounds(&self) -> Option<(&Arc<ImageData>, Rect, Filter)> {
+      match self {
+         Texture::Image { data, filter } => Some((data, data.full(), *filter)),
+         Texture::AtlasRegion { data, rect, filter } => Some((data, *rect, *filter)),
+         _ => None,
+      }
+   }
+
+   /// Todas las baldosas de la grilla en orden de `tile` (vacío si no es una imagen).
+   pub fn grid_tiles(&self, cols: i32, rows: i32) -> Vec<Self> {
+      (0..cols.max(0) * rows.max(0)).map_while(|i| self.tile(cols, rows, i)).collect()
+   }
+
+   /// Carga un atlas en grilla (terrain sheet) y nombra sus baldosas en orden; `_` salta una.
+   /// `Ok(None)` si la imagen no está; error si hay más nombres que baldosas.
+   pub fn load_sheet(path: &str, cols: i32, rows: i32, names: &[&str]) -> Result<Option<HashMap<String, Self>>, String> {
+      let count = cols.max(0) as usize * rows.max(0) as usize;
+      if names.len() > count { return Err(format!("{} nombres para una grilla de {count} baldosas", names.len())); }
+      let Some(sheet) = Texture::from_file(path) else { return Ok(None) };
+      let tiles = sheet.grid_tiles(cols, rows);
+      if tiles.len() < names.len() { return Err(format!("{path}: la imagen es más chica que la grilla {cols}x{rows}")); }
+      Ok(Some(names.iter().zip(tiles).filter(|(n, _)| **n != "_").map(|(n, t)| (n.to_string(), t)).collect()))
    }
 
    pub fn block_atlas_from_files(top: &str, side: &str, bottom: &str) -> Option<Self> {
       Some(Texture::BlockAtlas {
-         top: Box::new(Texture::from_file(top)?),
-         side: Box::new(Texture::from_file(side)?),
-         bottom: Box::new(Texture::from_file(bottom)?),
+         top: Arc::new(Texture::from_file(top)?),
+         side: Arc::new(Texture::from_file(side)?),
+         bottom: Arc::new(Texture::from_file(bottom)?),
       })
    }
 }
@@ -0,0 +1,100 @@
+// ==========================================================
+// Tonemapping: radiancia HDR (lineal, sin límite) → lineal de pantalla [0,1]
+// ==========================================================
+// Se aplica una sola vez, al convertir el framebuffer para mostrar o guardar;
+// el integrador y la acumulación trabajan siempre con valores sin recortar.
+use crate::math::Vec3;
+
+/// Curva de compresión del rango dinámico.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Operator {
+   Clamp,    // recorte duro a [0,1] (el comportamiento LDR de antes)
+   Reinhard, // L/(1+L) sobre la luminancia, conserva el tono
+   #[default]
+   Aces,     // ajuste filmico de Narkowicz a la curva ACES
+   Hable,    // Uncharted 2 (John Hable)
+   Agx,      // AgX (aproximación polinómica), satura menos los brillos
+}
+
+impl Operator {
+   pub const ALL: [Operator; 5] = [Operator::Clamp, Operator::Reinhard, Operator::Aces, Operator::Hable, Operator::Agx];
+
+   pub fn name(self) -> &'static str {
+      match self {
+         Operator::Clamp => "clamp",
+         Operator::Reinhard => "reinhard",
+         Operator::Aces => "aces",
+         Operator::Hable => "hable",
+         Operator::Agx => "agx",
+      }
+   }
+
+   pub fn from_name(s: &str) -> Option<Self> { Self::ALL.into_iter().find(|op| op.name() == s) }
+
+   /// Siguiente operador (la tecla T del visor los recorre en orden).
+   pub fn next(self) -> Self {
+      let i = Self::ALL.iter().position(|&op| op == self).unwrap_or(0);
+      Self::ALL[(i + 1) % Self::ALL.len()]
+   }
+}
+
+/// Operador + exposición en pasos (EV): el color se escala por 2^exposure antes de la curva.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tonemap {
+   pub op: Operator,
+   pub exposure: f32,
+}
+
+impl Tonemap {
+   pub fn apply(&self, c: Vec3) -> Vec3 {
+      let c = c.max(Vec3::new(0.0, 0.0, 0.0)).mul(self.exposure.exp2());
+      match self.op {
+         Operator::Clamp => c,
+         Operator::Reinhard => {
+            let l = luminance(c);
+            if l > 0.0 { c.mul(1.0 / (1.0 + l)) } else { c }
+         }
+         Operator::Aces => per_channel(c, |x| (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)),
+         Operator::Hable => {
+            const W: f32 = 11.2; // punto blanco lineal
+            let white = 1.0 / hable(W);
+            per_channel(c, |x| hable(2.0 * x) * white)
+         }
+         Operator::Agx => agx(c),
+      }
+      .clamp01()
+   }
+}
+
+fn luminance(c: Vec3) -> f32 { 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z }
+
+fn per_channel(c: Vec3, f: impl Fn(f32) -> f32) -> Vec3 { Vec3::new(f(c.x), f(c.y), f(c.z)) }
+
+fn hable(x: f32) -> f32 {
+   let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+   ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+// AgX mínimo: matriz de entrada, codificación log2 en [-12.47, 4.03] EV, sigmoide polinómica,
+// matriz de salida y vuelta a lineal (γ 2.2)
+fn agx(c: Vec3) -> Vec3 {
+   const MIN_EV: f32 = -12.473_93;
+   const MAX_EV: f32 = 4.026_069;
+   let inset = mat3(c, [0.842_479, 0.042_328_24, 0.042_375_65], [0.078_433_6, 0.878_468_6, 0.078_433_6], [0.079_224, 0.079_166, 0.879_143]);
+   let curve = per_channel(inset, |x| {
+      let x = ((x.max(1e-10).log2().clamp(MIN_EV, MAX_EV)) - MIN_EV) / (MAX_EV - MIN_EV);
+      let (x2, x4) = (x * x, x * x * x * x);
+      15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232
+   });
+   let outset = mat3(curve, [1.196_879, -0.052_896_85, -0.052_971_64], [-0.098_020_88, 1.151_903_1, -0.098_043_45], [-0.099_029_74, -0.098_961_18, 1.151_073_7]);
+   per_channel(outset, |x| x.max(0.0).powf(2.2))
+}
+
+// Matriz 3×3 dada por columnas, por vector
+fn mat3(v: Vec3, c0: [f32; 3], c1: [f32; 3], c2: [f32; 3]) -> Vec3 {
+   Vec3::new(
+      c0[0] * v.x + c1[0] * v.y + c2[0] * v.z,
+      c0[1] * v.x + c1[1] * v.y + c2[1] * v.z,
+      c0[2] * v.x + c1[2] * v.y + c2[2] * v.z,
+   )
+}